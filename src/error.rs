@@ -0,0 +1,113 @@
+use crate::{ObjFunc, Report};
+use ndarray::ArrayView2;
+use std::{
+    any::Any,
+    fmt::{Display, Formatter, Result as FmtResult},
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+/// The error type of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// The objective function returned an error.
+    Objective(Box<dyn std::error::Error + Send + Sync>),
+    /// The objective function panicked, with the panic message.
+    Panic(String),
+    /// The termination condition has an empty [`crate::Task::AnyOf`] or [`crate::Task::AllOf`].
+    EmptyTask,
+}
+
+impl Error {
+    /// Create an error of the objective function.
+    ///
+    /// ```
+    /// use metaheuristics_nature::Error;
+    ///
+    /// let e = Error::objective("failed to converge");
+    /// assert_eq!(e.to_string(), "objective function failed: failed to converge");
+    /// ```
+    pub fn objective<E>(e: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self::Objective(e.into())
+    }
+}
+
+fn panic_msg(e: Box<dyn Any + Send>) -> String {
+    match e.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(e) => match e.downcast::<&str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Objective(e) => write!(f, "objective function failed: {}", e),
+            Self::Panic(msg) => write!(f, "objective function panicked: {}", msg),
+            Self::EmptyTask => write!(f, "empty conditions of the task"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Objective(e) => Some(e.as_ref()),
+            Self::Panic(_) | Self::EmptyTask => None,
+        }
+    }
+}
+
+/// The policy of the failed evaluations.
+///
+/// The evaluation is failed if [`ObjFunc::try_fitness`] returns an error,
+/// or the objective function panicked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Treat the failed candidates as the worst (positive infinity).
+    Worst,
+    /// Retry the failed candidates N times,
+    /// then treat them as the worst if they are still failed.
+    Retry(u32),
+    /// Abort the run, the error will be returned by [`crate::Solver::try_solve`].
+    Abort,
+}
+
+/// Call the evaluation `f` of a candidate and turn the panic into an error.
+pub(crate) fn catch<T, G>(f: G) -> Result<T, Error>
+where
+    G: FnOnce() -> Result<T, Error>,
+{
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|e| Err(Error::Panic(panic_msg(e))))
+}
+
+/// Call the evaluation `f` of `n` candidates and turn the panic into errors.
+pub(crate) fn catch_batch<T, G>(n: usize, f: G) -> Vec<Result<T, Error>>
+where
+    G: FnOnce() -> Vec<Result<T, Error>>,
+{
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(ans) => ans,
+        Err(e) => {
+            let msg = panic_msg(e);
+            (0..n).map(|_| Err(Error::Panic(msg.clone()))).collect()
+        }
+    }
+}
+
+/// Call [`ObjFunc::try_fitness_batch`] and turn the panic into errors.
+///
+/// The default implementation catches the panic of each candidate,
+/// so the whole batch is failed only if the user implementation panicked.
+pub(crate) fn try_fitness_batch<F: ObjFunc>(
+    func: &F,
+    v: ArrayView2<f64>,
+    report: &Report,
+) -> Vec<Result<f64, Error>> {
+    catch_batch(v.nrows(), || func.try_fitness_batch(v, report))
+}
@@ -1,6 +1,6 @@
 //! A collection of nature-inspired metaheuristic algorithms.
 //! ```
-//! use metaheuristics_nature::{Report, RGA, RGASetting, Solver, Task, ObjFunc};
+//! use metaheuristics_nature::{Report, RGA, RGASetting, Solution, Solver, Task, ObjFunc};
 //! # use ndarray::{Array1, AsArray, ArrayView1};
 //! # struct MyFunc(Array1<f64>, Array1<f64>);
 //! # impl MyFunc {
@@ -21,8 +21,8 @@
 //! #     {
 //! #         self.fitness(v, &Default::default())
 //! #     }
-//! #     fn ub(&self) -> ArrayView1<f64> { self.1.view() }
-//! #     fn lb(&self) -> ArrayView1<f64> { self.0.view() }
+//! #     fn ub(&self) -> ArrayView1<'_, f64> { self.1.view() }
+//! #     fn lb(&self) -> ArrayView1<'_, f64> { self.0.view() }
 //! # }
 //!
 //! let a = RGA::solve(
@@ -33,6 +33,10 @@
 //! let ans: f64 = a.result(); // Get the result from objective function
 //! let (x, y): (Array1<f64>, f64) = a.parameters(); // Get the optimized XY value of your function
 //! let history: Vec<Report> = a.history(); // Get the history reports
+//! let s: Solution<MyFunc> = a.into_solution(); // Take all of them with the termination reason
+//! // Or solve into the solution directly
+//! let s = RGA::solution(MyFunc::new(), RGASetting::default().task(Task::MaxGen(20)), ());
+//! println!("{:?}", s.termination);
 //! ```
 //!
 //! There are two traits [`Algorithm`] and [`Solver`].
@@ -50,18 +54,56 @@
 //!
 //! The final answer is [`ObjFunc::result`], which is generated from the design parameters.
 //!
+//! If the objective function may fail, implement [`ObjFunc::try_fitness`] to return an [`Error`].
+//! The failed evaluations are handled by the [`FailurePolicy`] of the settings,
+//! and [`Solver::try_solve`] returns the error if the run is aborted.
+//!
+//! The integer and discrete variables can be declared by [`ObjFunc::variable`],
+//! the algorithms will only produce the valid values of them,
+//! and [`Solver::values`] returns the typed [`Value`]s.
+//!
+//! The constraints can be defined by [`ObjFunc::inequality`] and [`ObjFunc::equality`].
+//! The infeasible candidates are ranked by their total violation with the [`ConstraintHandling`],
+//! and the best feasible fitness is recorded in [`Report::feasible_f`].
+//!
+//! # Multi-objective
+//!
+//! The multi-objective problems can be defined by [`MultiObjFunc`],
+//! and solved by the multi-objective methods [`NSGA2`] and [`MOEAD`].
+//! The whole Pareto front is obtained by [`MultiObjSolver::pareto_front`].
+//!
+//! # Random Seed
+//!
+//! All algorithms draw their random numbers from [`AlgorithmBase::rng`],
+//! which is created by the `seed` option of the settings.
+//! Two runs with the same seed, objective function and settings have the same result.
+//! A random seed is chosen if the option is `None`.
+//!
 //! # Features
 //!
-//! + `parallel`: Enable parallel function, let objective function running without ordered,
-//!   uses [`std::thread::spawn`].
+//! + `parallel`: Enable parallel function, let objective function running in parallel,
+//!   uses a persistent worker pool `thread_pool::ThreadPool`.
+//!   The number of workers is decided by the `threads` option of the settings.
+//!   All the algorithms generate their candidates first, then evaluate them in parallel.
+//!   The random numbers are still drawn in order and the results are collected in order,
+//!   so the result is the same as the serial version with the same seed.
 //!   Disable it for the platform that doesn't supported threading,
 //!   or if your objective function is not complicate enough.
 pub use crate::callback::*;
+pub use crate::error::*;
 pub use crate::methods::*;
+pub use crate::multi_obj::*;
 pub use crate::obj_func::*;
+pub use crate::random::*;
+pub use crate::solution::*;
 pub use crate::utility::*;
+pub use crate::variable::*;
 
 /// Generate random values between [0., 1.) or by range.
+///
+/// The values are not reproducible by the seed,
+/// please use [`AlgorithmBase::rng`] in the algorithms instead.
+#[deprecated(note = "please use `AlgorithmBase::rng` instead, which follows the seed")]
 #[macro_export]
 macro_rules! rand {
     ($lb:expr, $ub:expr) => {{
@@ -74,6 +116,10 @@ macro_rules! rand {
 }
 
 /// Generate random boolean by positive factor.
+///
+/// The values are not reproducible by the seed,
+/// please use [`AlgorithmBase::rng`] in the algorithms instead.
+#[deprecated(note = "please use `AlgorithmBase::rng` instead, which follows the seed")]
 #[macro_export]
 macro_rules! maybe {
     ($v:expr) => {{
@@ -125,6 +171,20 @@ macro_rules! setting_builder {
                 pop_num: usize,
                 /// The report frequency. (per generation)
                 rpt: u32,
+                /// Random seed.
+                seed: Option<u64>,
+                /// Number of the worker threads.
+                threads: usize,
+                /// The policy of the failed evaluations.
+                failure: $crate::FailurePolicy,
+                /// The boundary handling strategy.
+                boundary: $crate::BoundaryHandling,
+                /// The constraint handling strategy.
+                constraint: $crate::ConstraintHandling,
+                /// The tolerance of the equality constraints.
+                eq_tol: f64,
+                /// Record the population statistics in the reports.
+                stats: bool,
             })?
             $($(#[$field_attr])* pub fn $field(mut self, $field: $field_type) -> Self {
                 self.$field = $field;
@@ -149,10 +209,15 @@ macro_rules! setting_builder {
 }
 
 mod callback;
+mod error;
 mod methods;
+mod multi_obj;
 mod obj_func;
+mod random;
+mod solution;
 #[cfg(test)]
 mod tests;
 #[cfg(feature = "parallel")]
 pub mod thread_pool;
 mod utility;
+mod variable;
@@ -0,0 +1,292 @@
+use self::Adaptation::*;
+use super::de::{vector, Recombination};
+use crate::{Strategy::CurrentToPBest1, *};
+use ndarray::{s, Array1, Array2, Axis};
+
+/// The parameter adaptation of the adaptive Differential Evolution.
+#[derive(Clone)]
+pub enum Adaptation {
+    /// Move the means of the parameters to the successful values
+    /// with the learning rate `c`. (JADE)
+    JADE,
+    /// Store the means of the successful parameters in the history memory. (SHADE)
+    SHADE,
+    /// SHADE with the linear population size reduction,
+    /// the population number is reduced to `min_pop` at the end of the task. (L-SHADE)
+    ///
+    /// The reduction follows [`AlgorithmBase::progress`].
+    LSHADE,
+}
+
+setting_builder! {
+    /// Adaptive Differential Evolution settings.
+    pub struct ADESetting {
+        @base,
+        @pop_num = 100,
+        @boundary = BoundaryHandling::Midpoint,
+        /// The parameter adaptation.
+        adaptation: Adaptation = SHADE,
+        /// Ratio of the top individuals that can be chosen as the *pbest*.
+        p: f64 = 0.11,
+        /// Size ratio of the external archive to the population, use zero to disable it.
+        archive: f64 = 1.,
+        /// Number of the history memory slots of SHADE and L-SHADE.
+        memory: usize = 6,
+        /// Learning rate of JADE.
+        c: f64 = 0.1,
+        /// Final population number of L-SHADE.
+        min_pop: usize = 4,
+    }
+}
+
+/// Mean of the values `x` with weights `w`,
+/// or the Lehmer mean *sum(w * x^2) / sum(w * x)* if `lehmer` is true.
+fn mean(v: &[(f64, f64)], lehmer: bool) -> f64 {
+    let (num, den) = v.iter().fold((0., 0.), |(num, den), &(x, w)| {
+        if lehmer {
+            (num + w * x * x, den + w * x)
+        } else {
+            (num + w * x, den + w)
+        }
+    });
+    if den > 0. {
+        num / den
+    } else {
+        0.
+    }
+}
+
+/// Adaptive Differential Evolution type.
+///
+/// The trial vectors are generated by the current-to-pbest/1 mutation and the binomial crossover,
+/// as the same as [`Strategy::CurrentToPBest1`] of [`DE`].
+/// The second difference vector can be chosen from the external archive of the replaced parents.
+///
+/// Unlike [`DE`], the default boundary handling is [`BoundaryHandling::Midpoint`],
+/// which is the repair of the original JADE and SHADE,
+/// so the trial vectors near the bounds are not wasted.
+///
+/// The F factor and the crossing probability of each trial vector
+/// are sampled around the adapted means,
+/// which are shown in [`Report::f_mean`] and [`Report::cr_mean`].
+///
+/// The population number is at least 4, as the same as the final population of L-SHADE.
+pub struct ADE<F: ObjFunc> {
+    adaptation: Adaptation,
+    p: f64,
+    archive_rate: f64,
+    c: f64,
+    pop_init: usize,
+    min_pop: usize,
+    // The memory of the means, the terminal value of L-SHADE is `None`
+    m_f: Vec<f64>,
+    m_cr: Vec<Option<f64>>,
+    k: usize,
+    archive: Vec<Array1<f64>>,
+    v: [usize; 2],
+    tmp: Array1<f64>,
+    base: AlgorithmBase<F>,
+}
+
+impl<F> ADE<F>
+where
+    F: ObjFunc,
+{
+    fn update_memory(&mut self, success: &[(f64, f64, f64)]) {
+        if success.is_empty() {
+            return;
+        }
+        let weight = |w: f64| if let JADE = self.adaptation { 1. } else { w };
+        let s_f = success
+            .iter()
+            .map(|&(f, _, w)| (f, weight(w)))
+            .collect::<Vec<_>>();
+        let s_cr = success
+            .iter()
+            .map(|&(_, cr, w)| (cr, weight(w)))
+            .collect::<Vec<_>>();
+        let k = self.k;
+        match self.adaptation {
+            JADE => {
+                let m_cr = self.m_cr[0].unwrap_or(0.);
+                self.m_f[0] = (1. - self.c) * self.m_f[0] + self.c * mean(&s_f, true);
+                self.m_cr[0] = Some((1. - self.c) * m_cr + self.c * mean(&s_cr, false));
+            }
+            SHADE => {
+                self.m_f[k] = mean(&s_f, true);
+                self.m_cr[k] = Some(mean(&s_cr, false));
+            }
+            LSHADE => {
+                self.m_f[k] = mean(&s_f, true);
+                self.m_cr[k] = match self.m_cr[k] {
+                    Some(_) if s_cr.iter().any(|&(cr, _)| cr > 0.) => Some(mean(&s_cr, true)),
+                    _ => None,
+                };
+            }
+        }
+        self.k = (k + 1) % self.m_f.len();
+    }
+
+    /// Linear population size reduction, remove the worst individuals.
+    fn reduce(&mut self) {
+        let (init, min) = (self.pop_init as f64, self.min_pop as f64);
+        let n = (init + (min - init) * self.base.progress()).round() as usize;
+        if n >= self.base.pop_num {
+            return;
+        }
+        let mut order = self.base.ranking();
+        order.truncate(n);
+        let b = &mut self.base;
+        b.pool = b.pool.select(Axis(0), &order);
+        b.fitness = b.fitness.select(Axis(0), &order);
+        b.violation = b.violation.select(Axis(0), &order);
+        b.pop_num = n;
+    }
+
+    fn trim_archive(&mut self) {
+        let size = (self.archive_rate * self.base.pop_num as f64).round() as usize;
+        while self.archive.len() > size {
+            let i = self.base.rng.int(0, self.archive.len());
+            self.archive.swap_remove(i);
+        }
+    }
+
+    fn update_report(&mut self) {
+        let n = self.m_f.len() as f64;
+        let r = &mut self.base.report;
+        r.f_mean = Some(self.m_f.iter().sum::<f64>() / n);
+        r.cr_mean = Some(self.m_cr.iter().map(|m| m.unwrap_or(0.)).sum::<f64>() / n);
+    }
+}
+
+impl<F> Algorithm<F> for ADE<F>
+where
+    F: ObjFunc,
+{
+    type Setting = ADESetting;
+
+    fn create(func: F, settings: Self::Setting) -> Self {
+        // The current-to-pbest/1 formula needs two distinct random vectors
+        let base = AlgorithmBase::new(func, settings.base.pop_at_least(4));
+        let memory = match settings.adaptation {
+            JADE => 1,
+            SHADE | LSHADE => settings.memory.max(1),
+        };
+        Self {
+            adaptation: settings.adaptation,
+            p: settings.p,
+            archive_rate: settings.archive,
+            c: settings.c,
+            pop_init: base.pop_num,
+            min_pop: settings.min_pop.max(4),
+            m_f: vec![0.5; memory],
+            m_cr: vec![Some(0.5); memory],
+            k: 0,
+            archive: Vec::new(),
+            v: [0; 2],
+            tmp: Array1::zeros(base.dim),
+            base,
+        }
+    }
+
+    #[inline(always)]
+    fn base(&self) -> &AlgorithmBase<F> {
+        &self.base
+    }
+
+    #[inline(always)]
+    fn base_mut(&mut self) -> &mut AlgorithmBase<F> {
+        &mut self.base
+    }
+
+    fn init(&mut self) {
+        self.update_report();
+    }
+
+    fn generation(&mut self) {
+        let pop_num = self.base.pop_num;
+        let dim = self.base.dim;
+        let order = self.base.ranking();
+        let p_num = ((self.p * pop_num as f64).round() as usize).clamp(2, pop_num);
+        let all = (0..pop_num).collect::<Vec<_>>();
+        let mut index = Vec::with_capacity(pop_num);
+        let mut param = Vec::with_capacity(pop_num);
+        let mut trial = Vec::with_capacity(pop_num * dim);
+        for i in 0..pop_num {
+            let rng = &mut self.base.rng;
+            let r = rng.int(0, self.m_f.len());
+            let cr = match self.m_cr[r] {
+                Some(m) => rng.normal(m, 0.1).clamp(0., 1.),
+                None => 0.,
+            };
+            let f = loop {
+                let f = rng.cauchy(self.m_f[r], 0.1);
+                if f > 0. {
+                    break f.min(1.);
+                }
+            };
+            let pbest = order[rng.int(0, p_num)];
+            vector(rng, i, &all, &mut self.v[..1]);
+            let r2 = loop {
+                let r2 = rng.int(0, pop_num + self.archive.len());
+                if r2 != i && r2 != self.v[0] {
+                    break r2;
+                }
+            };
+            // The second random vector may be chosen from the archive
+            let archive = self.archive.get(r2.wrapping_sub(pop_num)).map(Array1::view);
+            if archive.is_none() {
+                self.v[1] = r2;
+            }
+            self.tmp.assign(&self.base.pool.slice(s![i, ..]));
+            Recombination {
+                strategy: &CurrentToPBest1(self.p),
+                f,
+                // The recombination mutates the variable if the probability is not hit
+                cross: 1. - cr,
+                jitter: 0.,
+                pool: self.base.pool.view(),
+                fitness: self.base.fitness.view(),
+                best: self.base.pool.slice(s![pbest, ..]),
+                v: &self.v,
+                archive,
+            }
+            .apply(&mut self.base.rng, &mut self.tmp);
+            for s in 0..dim {
+                self.tmp[s] = self.check(s, self.tmp[s], self.base.pool[[i, s]]);
+            }
+            if self.base.is_discarded(&self.tmp) {
+                continue;
+            }
+            index.push(i);
+            param.push((f, cr));
+            trial.extend(self.tmp.iter());
+        }
+        let trial = Array2::from_shape_vec((index.len(), dim), trial).unwrap();
+        let (fitness, violation) = self.base.evaluate(trial.view());
+        let mut success = Vec::with_capacity(index.len());
+        for (n, &i) in index.iter().enumerate() {
+            let new = (fitness[n], violation[n]);
+            let old = (self.base.fitness[i], self.base.violation[i]);
+            if !self.base.is_better_than(new, old) {
+                continue;
+            }
+            if self.archive_rate > 0. {
+                self.archive
+                    .push(self.base.pool.slice(s![i, ..]).to_owned());
+            }
+            // The improvement is the weight of the parameters
+            let w = (old.0 - new.0).abs() + (old.1 - new.1).abs();
+            let w = if w.is_finite() { w } else { 1. };
+            success.push((param[n].0, param[n].1, w));
+            self.assign_from(i, new.0, new.1, trial.slice(s![n, ..]));
+        }
+        self.update_memory(&success);
+        if let LSHADE = self.adaptation {
+            self.reduce();
+        }
+        self.trim_archive();
+        self.find_best();
+        self.update_report();
+    }
+}
@@ -0,0 +1,404 @@
+use crate::*;
+use ndarray::{s, Array1, Array2, Axis};
+use std::collections::VecDeque;
+
+/// The recombination weights of the parents, where *i* is the rank of the parent.
+#[derive(Clone)]
+pub enum Weighting {
+    /// *ln(mu + 1/2) - ln(i)*
+    Log,
+    /// *mu + 1 - i*
+    Linear,
+    /// Equal weights.
+    Equal,
+}
+
+/// The restart strategy of CMA-ES.
+///
+/// The run is restarted if the step size or the fitness is stagnated,
+/// or the covariance matrix is ill-conditioned.
+#[derive(Clone)]
+pub enum Restart {
+    /// Never restart.
+    Never,
+    /// Restart with the doubled population number,
+    /// the population is doubled at most 9 times. (IPOP-CMA-ES)
+    IPOP,
+    /// Alternate the large population regime and the small population regime,
+    /// the small population has a random step size. (BIPOP-CMA-ES)
+    BIPOP,
+}
+
+setting_builder! {
+    /// Covariance Matrix Adaptation Evolution Strategy settings.
+    ///
+    /// The population number is decided by `lambda`,
+    /// the `pop_num` option is the alias of `lambda` if `lambda` is zero.
+    pub struct CMAESSetting {
+        @base,
+        @pop_num = 0,
+        /// Initial step size, the ratio to the range of the bounds.
+        sigma: f64 = 0.3,
+        /// Number of the offspring, use zero to choose the population number,
+        /// or *4 + 3 ln(dim)* if both are zero.
+        lambda: usize = 0,
+        /// Number of the parents, use zero to choose *lambda / 2*.
+        mu: usize = 0,
+        /// The recombination weights of the parents.
+        weighting: Weighting = Weighting::Log,
+        /// The restart strategy.
+        restart: Restart = Restart::Never,
+    }
+}
+
+/// Eigen decomposition of the symmetric matrix by the cyclic Jacobi method.
+///
+/// Return the eigenvalues and the eigenvectors (columns).
+fn eigen(a: &Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = a.nrows();
+    let mut a = a.clone();
+    let mut v = Array2::eye(n);
+    let total = a.mapv(|x| x * x).sum();
+    for _ in 0..100 {
+        let mut off = 0.;
+        for p in 0..n {
+            for q in p + 1..n {
+                off += a[[p, q]] * a[[p, q]];
+            }
+        }
+        if off <= f64::EPSILON * f64::EPSILON * total {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[[p, q]] == 0. {
+                    continue;
+                }
+                let theta = (a[[q, q]] - a[[p, p]]) / (2. * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                let c = 1. / (t * t + 1.).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (kp, kq) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = c * kp - s * kq;
+                    a[[k, q]] = s * kp + c * kq;
+                }
+                for k in 0..n {
+                    let (pk, qk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * pk - s * qk;
+                    a[[q, k]] = s * pk + c * qk;
+                }
+                for k in 0..n {
+                    let (kp, kq) = (v[[k, p]], v[[k, q]]);
+                    v[[k, p]] = c * kp - s * kq;
+                    v[[k, q]] = s * kp + c * kq;
+                }
+            }
+        }
+    }
+    (a.diag().to_owned(), v)
+}
+
+/// Covariance Matrix Adaptation Evolution Strategy type.
+///
+/// The distribution is adapted in the normalized space of the bounds,
+/// and the samples are repaired by the [`BoundaryHandling`] strategy.
+/// The discarded samples will be resampled.
+pub struct CMAES<F: ObjFunc> {
+    sigma0: f64,
+    lambda0: usize,
+    mu0: usize,
+    weighting: Weighting,
+    restart: Restart,
+    // The state of the current run
+    mu: usize,
+    weights: Array1<f64>,
+    mueff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    chi_n: f64,
+    mean: Array1<f64>,
+    sigma: f64,
+    c: Array2<f64>,
+    b: Array2<f64>,
+    d: Array1<f64>,
+    pc: Array1<f64>,
+    ps: Array1<f64>,
+    gen: u32,
+    history: VecDeque<f64>,
+    // The budget of the restarts
+    large: u32,
+    small: bool,
+    eval_large: usize,
+    eval_small: usize,
+    base: AlgorithmBase<F>,
+}
+
+impl<F> CMAES<F>
+where
+    F: ObjFunc,
+{
+    fn to_real(&self, s: usize, y: f64) -> f64 {
+        self.lb(s) + y * (self.ub(s) - self.lb(s))
+    }
+
+    fn to_norm(&self, s: usize, x: f64) -> f64 {
+        let w = self.ub(s) - self.lb(s);
+        if w > 0. {
+            (x - self.lb(s)) / w
+        } else {
+            0.
+        }
+    }
+
+    /// Start a new run with `lambda` offspring and step size `sigma`.
+    fn start(&mut self, lambda: usize, sigma: f64) {
+        let n = self.base.dim;
+        let nf = n as f64;
+        let lambda = lambda.max(2);
+        self.mu = if self.mu0 == 0 {
+            lambda / 2
+        } else {
+            self.mu0 * lambda / self.lambda0
+        }
+        .clamp(1, lambda);
+        let mu = self.mu as f64;
+        let w = (1..=self.mu).map(|i| match self.weighting {
+            Weighting::Log => (mu + 0.5).ln() - (i as f64).ln(),
+            Weighting::Linear => mu + 1. - i as f64,
+            Weighting::Equal => 1.,
+        });
+        self.weights = w.collect();
+        self.weights /= self.weights.sum();
+        self.mueff = 1. / self.weights.mapv(|w| w * w).sum();
+        let mueff = self.mueff;
+        self.cc = (4. + mueff / nf) / (nf + 4. + 2. * mueff / nf);
+        self.cs = (mueff + 2.) / (nf + mueff + 5.);
+        self.c1 = 2. / ((nf + 1.3).powi(2) + mueff);
+        self.cmu = (1. - self.c1).min(2. * (mueff - 2. + 1. / mueff) / ((nf + 2.).powi(2) + mueff));
+        self.damps = 1. + 2. * (((mueff - 1.) / (nf + 1.)).sqrt() - 1.).max(0.) + self.cs;
+        self.chi_n = nf.sqrt() * (1. - 1. / (4. * nf) + 1. / (21. * nf * nf));
+        self.mean = (0..n)
+            .map(|s| {
+                let x = self.base.random(s);
+                self.to_norm(s, x)
+            })
+            .collect();
+        self.sigma = sigma;
+        self.c = Array2::eye(n);
+        self.b = Array2::eye(n);
+        self.d = Array1::ones(n);
+        self.pc = Array1::zeros(n);
+        self.ps = Array1::zeros(n);
+        self.gen = 0;
+        self.history.clear();
+        self.base.pop_num = lambda;
+        self.base.pool = Array2::zeros((lambda, n));
+        self.base.fitness = Array1::zeros(lambda);
+        self.base.violation = Array1::zeros(lambda);
+    }
+
+    /// Return true if the run should be restarted.
+    fn stagnated(&self) -> bool {
+        let n = self.base.dim;
+        let tol_x = 1e-12 * self.sigma0;
+        let spread = (0..n)
+            .map(|s| self.pc[s].abs().max(self.c[[s, s]].sqrt()))
+            .fold(0., f64::max);
+        let len = 10 + (30 * n).div_ceil(self.base.pop_num);
+        let d_max = self.d.fold(0., |a: f64, &b| a.max(b));
+        let d_min = self.d.fold(f64::INFINITY, |a: f64, &b| a.min(b));
+        !self.sigma.is_finite()
+            || self.sigma * spread < tol_x
+            || d_max > 1e7 * d_min
+            || (self.history.len() >= len && {
+                let max = self
+                    .history
+                    .iter()
+                    .fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                let min = self.history.iter().fold(f64::INFINITY, |a, &b| a.min(b));
+                max - min < 1e-12
+            })
+    }
+
+    fn lambda_large(&self) -> usize {
+        self.lambda0 * 2usize.pow(self.large.min(9))
+    }
+
+    fn restart(&mut self) {
+        match self.restart {
+            Restart::Never => (),
+            Restart::BIPOP if self.large > 0 && self.eval_small < self.eval_large => {
+                let u = self.base.rng.rand();
+                let ratio = 0.5 * self.lambda_large() as f64 / self.lambda0 as f64;
+                let lambda = (self.lambda0 as f64 * ratio.powf(u * u)) as usize;
+                self.small = true;
+                self.start(lambda, self.sigma0 * 10f64.powf(-2. * u));
+            }
+            Restart::IPOP | Restart::BIPOP => {
+                self.large += 1;
+                self.small = false;
+                self.start(self.lambda_large(), self.sigma0);
+            }
+        }
+    }
+
+    /// Sample, evaluate and update the distribution.
+    fn step(&mut self, first: bool) {
+        let n = self.base.dim;
+        let lambda = self.base.pop_num;
+        for k in 0..lambda {
+            for attempt in 0.. {
+                let z = (0..n)
+                    .map(|_| self.base.rng.normal(0., 1.))
+                    .collect::<Array1<f64>>();
+                let y = self.b.dot(&(&self.d * &z));
+                for s in 0..n {
+                    let x = self.to_real(s, self.mean[s] + self.sigma * y[s]);
+                    let parent = self.to_real(s, self.mean[s]);
+                    self.base.pool[[k, s]] = self.check(s, x, parent);
+                }
+                if !self.base.is_discarded(self.base.pool.slice(s![k, ..])) {
+                    break;
+                }
+                if attempt == 99 {
+                    for s in 0..n {
+                        let (lb, ub) = (self.lb(s), self.ub(s));
+                        let x = self.base.pool[[k, s]].clamp(lb, ub);
+                        self.base.pool[[k, s]] = self.base.func.variable(s).snap(x, lb, ub);
+                    }
+                    break;
+                }
+            }
+        }
+        let (fitness, violation) = self.base.evaluate(self.base.pool.view());
+        self.base.fitness = fitness;
+        self.base.violation = violation;
+        let order = self.base.ranking();
+        let best = order[0];
+        if first
+            || self
+                .base
+                .is_best(self.base.fitness[best], self.base.violation[best])
+        {
+            self.base.set_best(best);
+        }
+        if self.small {
+            self.eval_small += lambda;
+        } else {
+            self.eval_large += lambda;
+        }
+        // Update the distribution with the repaired samples
+        let mut y = Array2::zeros((self.mu, n));
+        for (i, &k) in order[..self.mu].iter().enumerate() {
+            for s in 0..n {
+                y[[i, s]] = (self.to_norm(s, self.base.pool[[k, s]]) - self.mean[s]) / self.sigma;
+            }
+        }
+        let yw = self.weights.dot(&y);
+        self.mean += &(&yw * self.sigma);
+        let inv_sqrt = self.b.dot(&(&self.b.t().dot(&yw) / &self.d));
+        self.ps *= 1. - self.cs;
+        self.ps += &(inv_sqrt * (self.cs * (2. - self.cs) * self.mueff).sqrt());
+        let ps_norm = self.ps.dot(&self.ps).sqrt();
+        let nf = n as f64;
+        let hsig =
+            ps_norm / (1. - (1. - self.cs).powi(2 * (self.gen as i32 + 1))).sqrt() / self.chi_n
+                < 1.4 + 2. / (nf + 1.);
+        self.pc *= 1. - self.cc;
+        if hsig {
+            self.pc += &(&yw * (self.cc * (2. - self.cc) * self.mueff).sqrt());
+        }
+        let pc = self.pc.view().insert_axis(Axis(1));
+        let rank_one = pc.dot(&pc.t());
+        let rank_mu = y.t().dot(&(&y * &self.weights.view().insert_axis(Axis(1))));
+        let delta = if hsig { 0. } else { self.cc * (2. - self.cc) };
+        self.c *= 1. - self.c1 - self.cmu + self.c1 * delta;
+        self.c += &(rank_one * self.c1);
+        self.c += &(rank_mu * self.cmu);
+        self.c = (&self.c + &self.c.t()) * 0.5;
+        self.sigma *= ((self.cs / self.damps) * (ps_norm / self.chi_n - 1.)).exp();
+        let (d, b) = eigen(&self.c);
+        self.d = d.mapv(|d| d.max(1e-20).sqrt());
+        self.b = b;
+        self.gen += 1;
+        let len = 10 + (30 * n).div_ceil(lambda);
+        self.history.push_back(self.base.fitness[best]);
+        while self.history.len() > len {
+            self.history.pop_front();
+        }
+    }
+}
+
+impl<F> Algorithm<F> for CMAES<F>
+where
+    F: ObjFunc,
+{
+    type Setting = CMAESSetting;
+
+    fn create(func: F, settings: Self::Setting) -> Self {
+        let base = AlgorithmBase::new(func, settings.base);
+        let lambda0 = if settings.lambda > 0 {
+            settings.lambda
+        } else if base.pop_num > 0 {
+            base.pop_num
+        } else {
+            4 + (3. * (base.dim as f64).ln()).floor() as usize
+        };
+        Self {
+            sigma0: settings.sigma,
+            lambda0,
+            mu0: settings.mu,
+            weighting: settings.weighting,
+            restart: settings.restart,
+            mu: 0,
+            weights: Array1::zeros(0),
+            mueff: 0.,
+            cc: 0.,
+            cs: 0.,
+            c1: 0.,
+            cmu: 0.,
+            damps: 0.,
+            chi_n: 0.,
+            mean: Array1::zeros(0),
+            sigma: 0.,
+            c: Array2::zeros((0, 0)),
+            b: Array2::zeros((0, 0)),
+            d: Array1::zeros(0),
+            pc: Array1::zeros(0),
+            ps: Array1::zeros(0),
+            gen: 0,
+            history: VecDeque::new(),
+            large: 0,
+            small: false,
+            eval_large: 0,
+            eval_small: 0,
+            base,
+        }
+    }
+
+    #[inline(always)]
+    fn base(&self) -> &AlgorithmBase<F> {
+        &self.base
+    }
+
+    #[inline(always)]
+    fn base_mut(&mut self) -> &mut AlgorithmBase<F> {
+        &mut self.base
+    }
+
+    fn init_pop(&mut self) {
+        self.start(self.lambda0, self.sigma0);
+        self.step(true);
+    }
+
+    fn generation(&mut self) {
+        if self.stagnated() {
+            self.restart();
+        }
+        self.step(false);
+    }
+}
@@ -1,6 +1,6 @@
 use self::Strategy::*;
 use crate::*;
-use ndarray::{s, Array1};
+use ndarray::{s, Array1, Array2, ArrayView1, ArrayView2};
 
 /// The Differential Evolution strategy.
 /// Each strategy has different formulas on the recombination.
@@ -16,6 +16,13 @@ use ndarray::{s, Array1};
 /// + *f3*: self{n} + F * (best{n} - self{n} + v0{n} - v1{n})
 /// + *f4*: best{n} + F * (v0{n} + v1{n} - v2{n} - v3{n})
 /// + *f5*: v4{n} + F * (v0{n} + v1{n} - v2{n} - v3{n})
+/// + *f6*: v0{n} + F * (best{n} - v0{n} + v1{n} - v2{n} + v3{n} - v4{n})
+/// + *f7*: self{n} + K * (v0{n} - self{n}) + F * (v1{n} - v2{n})
+/// + *f8*: (v0{n} + v1{n} + v2{n}) / 3 + (p1 - p0) * (v0{n} - v1{n})
+///   + (p2 - p1) * (v1{n} - v2{n}) + (p0 - p2) * (v2{n} - v0{n})
+///
+/// Where *K* is a random value between [0, 1) of each individual,
+/// and *pi* is the ratio of the absolute fitness of *vi* to their sum.
 ///
 /// # Crossing formula
 ///
@@ -43,6 +50,28 @@ pub enum Strategy {
     S9,
     /// *f5* + *c2*
     S10,
+    /// *f3* + *c2*, where the best is randomly chosen from the top ratio of the population.
+    /// (current-to-pbest/1)
+    CurrentToPBest1(f64),
+    /// *f6* + *c2* (rand-to-best/2)
+    RandToBest2,
+    /// *f7* without crossing, which is rotation invariant. (current-to-rand/1)
+    CurrentToRand1,
+    /// *f8* + *c2* with the probability, otherwise *f2* + *c2*. (trigonometric mutation)
+    Trigonometric(f64),
+}
+
+/// The variation of the F factor.
+#[derive(Clone)]
+pub enum Dither {
+    /// Use the fixed F factor.
+    Fixed,
+    /// Choose F between the F factor and the value in each generation.
+    Generation(f64),
+    /// Choose F between the F factor and the value for each individual.
+    Individual(f64),
+    /// Jitter F of each variable by the scale, *F * (1 + scale * (rand - 0.5))*.
+    Jitter(f64),
 }
 
 setting_builder! {
@@ -50,93 +79,171 @@ setting_builder! {
     pub struct DESetting {
         @base,
         @pop_num = 400,
+        @boundary = BoundaryHandling::Discard,
         /// Strategy of the formula.
         strategy: Strategy = S1,
         /// F factor.
         f: f64 = 0.6,
         /// Crossing probability.
         cross: f64 = 0.9,
+        /// The variation of the F factor.
+        dither: Dither = Dither::Fixed,
     }
 }
 
-/// Differential Evolution type.
-pub struct DE<F: ObjFunc> {
-    f: f64,
-    cross: f64,
-    v: Array1<usize>,
-    tmp: Array1<f64>,
-    formula: fn(&Self, usize) -> f64,
-    setter: fn(&mut Self, usize),
-    base: AlgorithmBase<F>,
-}
-
-impl<F> DE<F>
-where
-    F: ObjFunc,
-{
-    fn vector(&mut self, i: usize) {
-        for j in 0..self.v.len() {
-            self.v[j] = i;
-            while self.v[j] == i || self.v.slice(s![..j]).iter().any(|&v| v == self.v[j]) {
-                self.v[j] = rand!(0, self.base.pop_num);
-            }
+impl Strategy {
+    /// Number of the random vectors of the formula.
+    pub(crate) fn vector_num(&self) -> usize {
+        match self {
+            S1 | S3 | S6 | S8 | CurrentToPBest1(_) => 2,
+            S2 | S7 | CurrentToRand1 | Trigonometric(_) => 3,
+            S4 | S9 => 4,
+            S5 | S10 | RandToBest2 => 5,
         }
     }
+}
 
-    fn f1(&self, n: usize) -> f64 {
-        self.base.best[n]
-            + self.f * (self.base.pool[[self.v[0], n]] - self.base.pool[[self.v[1], n]])
-    }
-
-    fn f2(&self, n: usize) -> f64 {
-        self.base.pool[[self.v[0], n]]
-            + self.f * (self.base.pool[[self.v[1], n]] - self.base.pool[[self.v[2], n]])
-    }
-
-    fn f3(&self, n: usize) -> f64 {
-        self.tmp[n]
-            + self.f
-                * (self.base.best[n] - self.tmp[n] + self.base.pool[[self.v[0], n]]
-                    - self.base.pool[[self.v[1], n]])
-    }
-
-    fn f4(&self, n: usize) -> f64 {
-        self.base.best[n] + self.f45(n)
-    }
-
-    fn f5(&self, n: usize) -> f64 {
-        self.base.pool[[self.v[4], n]] + self.f45(n)
+/// Choose the random vectors `v` from the `pool` indices,
+/// they are different from each other and the individual `i`.
+pub(crate) fn vector(rng: &mut Rng, i: usize, pool: &[usize], v: &mut [usize]) {
+    for j in 0..v.len() {
+        v[j] = i;
+        while v[j] == i || v[..j].iter().any(|&v_| v_ == v[j]) {
+            v[j] = pool[rng.int(0, pool.len())];
+        }
     }
+}
 
-    fn f45(&self, n: usize) -> f64 {
-        (self.base.pool[[self.v[0], n]] + self.base.pool[[self.v[1], n]]
-            - self.base.pool[[self.v[2], n]]
-            - self.base.pool[[self.v[3], n]])
-            * self.f
-    }
+/// The recombination of the Differential Evolution,
+/// which is also used by the DE-based methods.
+pub(crate) struct Recombination<'a> {
+    pub(crate) strategy: &'a Strategy,
+    pub(crate) f: f64,
+    pub(crate) cross: f64,
+    pub(crate) jitter: f64,
+    pub(crate) pool: ArrayView2<'a, f64>,
+    pub(crate) fitness: ArrayView1<'a, f64>,
+    pub(crate) best: ArrayView1<'a, f64>,
+    pub(crate) v: &'a [usize],
+    // The external vector that replaces the last random vector of the current-to-best formulas
+    pub(crate) archive: Option<ArrayView1<'a, f64>>,
+}
 
-    fn c1(&mut self, mut n: usize) {
-        for _ in 0..self.base.dim {
-            self.tmp[n] = (self.formula)(self, n);
-            n = (n + 1) % self.base.dim;
-            if !maybe!(self.cross) {
-                break;
+impl Recombination<'_> {
+    fn formula(&self, tmp: &Array1<f64>, n: usize, f: f64, k: f64, trig: bool) -> f64 {
+        let pool = &self.pool;
+        let v = self.v;
+        let f45 = || (pool[[v[0], n]] + pool[[v[1], n]] - pool[[v[2], n]] - pool[[v[3], n]]) * f;
+        match self.strategy {
+            S1 | S6 => self.best[n] + f * (pool[[v[0], n]] - pool[[v[1], n]]),
+            Trigonometric(_) if trig => {
+                let mut p = [0, 1, 2].map(|j| self.fitness[v[j]].abs());
+                let sum = p.iter().sum::<f64>();
+                if sum.is_finite() && sum > 0. {
+                    p.iter_mut().for_each(|p| *p /= sum);
+                } else {
+                    p = [1. / 3.; 3];
+                }
+                let x = [0, 1, 2].map(|j| pool[[v[j], n]]);
+                (x[0] + x[1] + x[2]) / 3.
+                    + (p[1] - p[0]) * (x[0] - x[1])
+                    + (p[2] - p[1]) * (x[1] - x[2])
+                    + (p[0] - p[2]) * (x[2] - x[0])
+            }
+            S2 | S7 | Trigonometric(_) => pool[[v[0], n]] + f * (pool[[v[1], n]] - pool[[v[2], n]]),
+            S3 | S8 | CurrentToPBest1(_) => {
+                let x2 = match self.archive {
+                    Some(x) => x[n],
+                    None => pool[[v[1], n]],
+                };
+                tmp[n] + f * (self.best[n] - tmp[n] + pool[[v[0], n]] - x2)
+            }
+            S4 | S9 => self.best[n] + f45(),
+            S5 | S10 => pool[[v[4], n]] + f45(),
+            RandToBest2 => {
+                pool[[v[0], n]]
+                    + f * (self.best[n] - pool[[v[0], n]] + pool[[v[1], n]] - pool[[v[2], n]]
+                        + pool[[v[3], n]]
+                        - pool[[v[4], n]])
+            }
+            CurrentToRand1 => {
+                tmp[n] + k * (pool[[v[0], n]] - tmp[n]) + f * (pool[[v[1], n]] - pool[[v[2], n]])
             }
         }
     }
 
-    fn c2(&mut self, mut n: usize) {
-        for lv in 0..self.base.dim {
-            if !maybe!(self.cross) || lv == self.base.dim - 1 {
-                self.tmp[n] = (self.formula)(self, n);
+    /// Apply the recombination on the copy of the target individual `tmp`.
+    pub(crate) fn apply(&self, rng: &mut Rng, tmp: &mut Array1<f64>) {
+        let dim = tmp.len();
+        let mut n = rng.int(0, dim);
+        let k = match self.strategy {
+            CurrentToRand1 => rng.rand(),
+            _ => 0.,
+        };
+        let trig = match self.strategy {
+            Trigonometric(p) => rng.maybe(*p),
+            _ => false,
+        };
+        let formula = |rng: &mut Rng, tmp: &Array1<f64>, n| {
+            let f = if self.jitter > 0. {
+                self.f * (1. + self.jitter * (rng.rand() - 0.5))
+            } else {
+                self.f
+            };
+            self.formula(tmp, n, f, k, trig)
+        };
+        match self.strategy {
+            S1 | S2 | S3 | S4 | S5 => {
+                for _ in 0..dim {
+                    tmp[n] = formula(rng, tmp, n);
+                    n = (n + 1) % dim;
+                    if !rng.maybe(self.cross) {
+                        break;
+                    }
+                }
+            }
+            S6 | S7 | S8 | S9 | S10 | CurrentToPBest1(_) | RandToBest2 | Trigonometric(_) => {
+                for lv in 0..dim {
+                    if !rng.maybe(self.cross) || lv == dim - 1 {
+                        tmp[n] = formula(rng, tmp, n);
+                    }
+                    n = (n + 1) % dim;
+                }
+            }
+            CurrentToRand1 => {
+                for n in 0..dim {
+                    tmp[n] = formula(rng, tmp, n);
+                }
             }
-            n = (n + 1) % self.base.dim;
         }
     }
+}
+
+/// Differential Evolution type.
+///
+/// The population number is raised to be more than the random vectors of the strategy.
+pub struct DE<F: ObjFunc> {
+    strategy: Strategy,
+    f: f64,
+    cross: f64,
+    dither: Dither,
+    v: Vec<usize>,
+    tmp: Array1<f64>,
+    base: AlgorithmBase<F>,
+}
 
-    fn recombination(&mut self, i: usize) {
-        self.tmp.assign(&self.base.pool.slice(s![i, ..]));
-        (self.setter)(self, rand!(0, self.base.dim));
+impl<F> DE<F>
+where
+    F: ObjFunc,
+{
+    /// Choose a F factor between the setting and `f`.
+    fn dither(&mut self, f: f64) -> f64 {
+        let (lb, ub) = (self.f.min(f), self.f.max(f));
+        if lb < ub {
+            self.base.rng.float(lb, ub)
+        } else {
+            lb
+        }
     }
 }
 
@@ -147,29 +254,16 @@ where
     type Setting = DESetting;
 
     fn create(func: F, settings: Self::Setting) -> Self {
-        let base = AlgorithmBase::new(func, settings.base);
-        let num = match settings.strategy {
-            S1 | S3 | S6 | S8 => 2,
-            S2 | S7 => 3,
-            S4 | S9 => 4,
-            S5 | S10 => 5,
-        };
+        // The random vectors are distinct from each other and the current individual
+        let v = settings.strategy.vector_num();
+        let base = AlgorithmBase::new(func, settings.base.pop_at_least(v + 1));
         Self {
+            v: vec![0; v],
+            strategy: settings.strategy,
             f: settings.f,
             cross: settings.cross,
-            v: Array1::zeros(num),
+            dither: settings.dither,
             tmp: Array1::zeros(base.dim),
-            formula: match settings.strategy {
-                S1 | S6 => Self::f1,
-                S2 | S7 => Self::f2,
-                S3 | S8 => Self::f3,
-                S4 | S9 => Self::f4,
-                S5 | S10 => Self::f5,
-            },
-            setter: match settings.strategy {
-                S1 | S2 | S3 | S4 | S5 => Self::c1,
-                S6 | S7 | S8 | S9 | S10 => Self::c2,
-            },
             base,
         }
     }
@@ -185,17 +279,66 @@ where
     }
 
     fn generation(&mut self) {
-        'a: for i in 0..self.base.pop_num {
-            self.vector(i);
-            self.recombination(i);
+        let mut index = Vec::with_capacity(self.base.pop_num);
+        let mut trial = Vec::with_capacity(self.base.pop_num * self.base.dim);
+        let pool = (0..self.base.pop_num).collect::<Vec<_>>();
+        let f = match self.dither {
+            Dither::Generation(f) => self.dither(f),
+            _ => self.f,
+        };
+        let jitter = match self.dither {
+            Dither::Jitter(scale) => scale,
+            _ => 0.,
+        };
+        let (order, p_num) = match self.strategy {
+            CurrentToPBest1(p) => {
+                let p_num = (p * self.base.pop_num as f64).round() as usize;
+                (self.base.ranking(), p_num.clamp(1, self.base.pop_num))
+            }
+            _ => (Vec::new(), 0),
+        };
+        for i in 0..self.base.pop_num {
+            vector(&mut self.base.rng, i, &pool, &mut self.v);
+            self.tmp.assign(&self.base.pool.slice(s![i, ..]));
+            let f = match self.dither {
+                Dither::Individual(f) => self.dither(f),
+                _ => f,
+            };
+            let best = if order.is_empty() {
+                self.base.best.view()
+            } else {
+                self.base.pool.row(order[self.base.rng.int(0, p_num)])
+            };
+            Recombination {
+                strategy: &self.strategy,
+                f,
+                cross: self.cross,
+                jitter,
+                pool: self.base.pool.view(),
+                fitness: self.base.fitness.view(),
+                best,
+                v: &self.v,
+                archive: None,
+            }
+            .apply(&mut self.base.rng, &mut self.tmp);
             for s in 0..self.base.dim {
-                if self.tmp[s] > self.ub(s) || self.tmp[s] < self.lb(s) {
-                    continue 'a;
-                }
+                self.tmp[s] = self.check(s, self.tmp[s], self.base.pool[[i, s]]);
             }
-            let tmp_f = self.base.func.fitness(&self.tmp, &self.base.report);
-            if tmp_f < self.base.fitness[i] {
-                self.assign_from(i, tmp_f, &self.tmp.clone());
+            if self.base.is_discarded(&self.tmp) {
+                continue;
+            }
+            index.push(i);
+            trial.extend(self.tmp.iter());
+        }
+        let trial = Array2::from_shape_vec((index.len(), self.base.dim), trial).unwrap();
+        let (fitness, violation) = self.base.evaluate(trial.view());
+        for (n, &i) in index.iter().enumerate() {
+            let f = (fitness[n], violation[n]);
+            if self
+                .base
+                .is_better_than(f, (self.base.fitness[i], self.base.violation[i]))
+            {
+                self.assign_from(i, f.0, f.1, trial.slice(s![n, ..]));
             }
         }
         self.find_best();
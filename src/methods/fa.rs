@@ -1,13 +1,16 @@
 use crate::*;
-use ndarray::{s, AsArray};
+use ndarray::{s, Array2, AsArray, Axis};
 
 setting_builder! {
     /// Firefly Algorithm settings.
     pub struct FASetting {
         @base,
         @pop_num = 80,
-        /// Alpha factor.
+        /// Alpha factor, the ratio of the random step to the range of the bounds.
         alpha: f64 = 0.01,
+        /// Final alpha factor, the alpha factor decays linearly to it with
+        /// [`AlgorithmBase::progress`], use `None` to keep the alpha factor constant.
+        alpha_end: Option<f64> = None,
         /// Minimum beta factor.
         beta_min: f64 = 0.2,
         /// Gamma factor.
@@ -34,6 +37,8 @@ where
 /// Firefly Algorithm type.
 pub struct FA<F: ObjFunc> {
     alpha: f64,
+    alpha0: f64,
+    alpha_end: Option<f64>,
     beta_min: f64,
     gamma: f64,
     beta0: f64,
@@ -44,40 +49,51 @@ impl<F> FA<F>
 where
     F: ObjFunc,
 {
-    fn move_firefly(&mut self, me: usize, she: usize) {
-        let r = distance(
-            self.base.pool.slice(s![me, ..]),
-            self.base.pool.slice(s![she, ..]),
-        );
+    fn move_firefly(&mut self, pool: &mut Array2<f64>, me: usize, she: usize) {
+        let r = distance(pool.slice(s![me, ..]), self.base.pool.slice(s![she, ..]));
         self.beta0 -= self.beta_min;
         let beta = self.beta0 * (-self.gamma * r).exp() + self.beta_min;
         for s in 0..self.base.dim {
-            let v = self.base.pool[[me, s]]
-                + beta * (self.base.pool[[she, s]] - self.base.pool[[me, s]])
-                + self.alpha * (self.ub(s) - self.lb(s)) * rand!(-0.5, 0.5);
-            self.base.pool[[me, s]] = self.check(s, v);
+            let v = pool[[me, s]]
+                + beta * (self.base.pool[[she, s]] - pool[[me, s]])
+                + self.alpha * (self.ub(s) - self.lb(s)) * self.base.rng.float(-0.5, 0.5);
+            pool[[me, s]] = self.check(s, v, pool[[me, s]]);
         }
     }
 
     fn move_fireflies(&mut self) {
+        let mut pool = self.base.pool.clone();
+        let mut index = Vec::with_capacity(self.base.pop_num);
         for i in 0..self.base.pop_num {
             let mut moved = false;
             for j in 0..self.base.pop_num {
-                if i == j || self.base.fitness[i] <= self.base.fitness[j] {
+                if i == j || !self.base.is_better_at(j, i) {
                     continue;
                 }
-                self.move_firefly(i, j);
+                self.move_firefly(&mut pool, i, j);
                 moved = true;
             }
-            if moved {
-                self.base.fitness(i);
-            } else {
+            if !moved {
                 for s in 0..self.base.dim {
-                    let v = self.base.pool[[i, s]]
-                        + self.alpha * (self.ub(s) - self.lb(s)) * rand!(-0.5, 0.5);
-                    self.base.pool[[i, s]] = self.check(s, v);
+                    let v = pool[[i, s]]
+                        + self.alpha * (self.ub(s) - self.lb(s)) * self.base.rng.float(-0.5, 0.5);
+                    pool[[i, s]] = self.check(s, v, pool[[i, s]]);
                 }
             }
+            if self.base.is_discarded(pool.slice(s![i, ..])) {
+                pool.slice_mut(s![i, ..])
+                    .assign(&self.base.pool.slice(s![i, ..]));
+                continue;
+            }
+            index.push(i);
+        }
+        self.base.pool = pool;
+        let (fitness, violation) = self
+            .base
+            .evaluate(self.base.pool.select(Axis(0), &index).view());
+        for (n, &i) in index.iter().enumerate() {
+            self.base.fitness[i] = fitness[n];
+            self.base.violation[i] = violation[n];
         }
     }
 }
@@ -92,6 +108,8 @@ where
         let base = AlgorithmBase::new(func, settings.base);
         Self {
             alpha: settings.alpha,
+            alpha0: settings.alpha,
+            alpha_end: settings.alpha_end,
             beta_min: settings.beta_min,
             gamma: settings.gamma,
             beta0: settings.beta0,
@@ -111,6 +129,9 @@ where
 
     #[inline(always)]
     fn generation(&mut self) {
+        if let Some(alpha_end) = self.alpha_end {
+            self.alpha = self.alpha0 + (alpha_end - self.alpha0) * self.base.progress();
+        }
         self.move_fireflies();
         self.find_best();
     }
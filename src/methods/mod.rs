@@ -1,11 +1,21 @@
+pub use self::ade::*;
+pub use self::cmaes::*;
 pub use self::de::*;
 pub use self::fa::*;
+pub use self::moead::*;
+pub use self::nsga2::*;
+pub use self::operator::*;
 pub use self::pso::*;
 pub use self::rga::*;
 pub use self::tlbo::*;
 
+mod ade;
+mod cmaes;
 mod de;
 mod fa;
+mod moead;
+mod nsga2;
+mod operator;
 mod pso;
 mod rga;
 mod tlbo;
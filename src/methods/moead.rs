@@ -0,0 +1,308 @@
+use self::Decomposition::*;
+use super::de::{vector, Recombination};
+use crate::{multi_obj::*, *};
+use ndarray::{s, Array1, Array2, ArrayView1, Axis};
+
+/// The scalarization of the subproblems.
+///
+/// Where *w* is the weight vector of the subproblem,
+/// and *z* is the ideal point, which is the minimum of each objective.
+#[derive(Clone)]
+pub enum Decomposition {
+    /// max{ w{n} * |f{n} - z{n}| }
+    Tchebycheff,
+    /// sum{ w{n} * f{n} }
+    WeightedSum,
+}
+
+impl Decomposition {
+    fn scalarize(&self, f: ArrayView1<f64>, w: ArrayView1<f64>, z: &Array1<f64>) -> f64 {
+        match self {
+            Tchebycheff => {
+                let mut ans = 0.;
+                for n in 0..f.len() {
+                    // Zero weight will ignore the objective
+                    let v = w[n].max(1e-6) * (f[n] - z[n]).abs();
+                    if v.is_nan() || v > ans {
+                        ans = v;
+                    }
+                }
+                ans
+            }
+            WeightedSum => (0..f.len()).map(|n| w[n] * f[n]).sum(),
+        }
+    }
+}
+
+setting_builder! {
+    /// Multi-objective Evolutionary Algorithm based on Decomposition settings.
+    pub struct MOEADSetting {
+        @base,
+        @pop_num = 100,
+        /// The scalarization of the subproblems.
+        decomposition: Decomposition = Tchebycheff,
+        /// Size of the neighborhood.
+        neighbor: usize = 20,
+        /// Probability of mating in the neighborhood.
+        delta: f64 = 0.9,
+        /// Maximum number of the solutions replaced by each child.
+        replace: usize = 2,
+        /// Strategy of the DE formula.
+        strategy: Strategy = Strategy::S7,
+        /// F factor of the DE formula.
+        f: f64 = 0.5,
+        /// Crossing probability of the DE formula, the standard CR of MOEA/D-DE,
+        /// which is the probability of each variable to take the mutated value.
+        cross: f64 = 1.,
+    }
+}
+
+/// Multi-objective Evolutionary Algorithm based on Decomposition type.
+///
+/// The uniform weight vectors are generated by [`uniform_weights`],
+/// which number is the largest one not greater than the population number.
+/// So the population number is decided at the creation, before any evaluation,
+/// and it is raised to have more weight vectors than the random vectors of the strategy.
+///
+/// The children are generated by the formulas of [`DE`],
+/// and the "best" of the formulas is the solution of the subproblem.
+/// The fitness of each individual is the scalarized value of its subproblem,
+/// please get the result from [`MultiObjSolver::pareto_front`].
+pub struct MOEAD<F: MultiObjFunc> {
+    decomposition: Decomposition,
+    neighbor: usize,
+    delta: f64,
+    replace: usize,
+    strategy: Strategy,
+    f: f64,
+    cross: f64,
+    v: Vec<usize>,
+    tmp: Array1<f64>,
+    weights: Array2<f64>,
+    neighbors: Vec<Vec<usize>>,
+    ideal: Array1<f64>,
+    objectives: Array2<f64>,
+    base: AlgorithmBase<F>,
+}
+
+impl<F> MOEAD<F>
+where
+    F: MultiObjFunc,
+{
+    fn scalarize(&self, f: ArrayView1<f64>, i: usize) -> f64 {
+        self.decomposition
+            .scalarize(f, self.weights.slice(s![i, ..]), &self.ideal)
+    }
+
+    fn update_ideal(&mut self, f: ArrayView1<f64>) {
+        for (z, &f) in self.ideal.iter_mut().zip(f.iter()) {
+            if is_better(f, *z) {
+                *z = f;
+            }
+        }
+    }
+
+    fn update_best(&mut self) {
+        for i in 0..self.base.pop_num {
+            self.base.fitness[i] = self.scalarize(self.objectives.slice(s![i, ..]), i);
+        }
+        let mut best = 0;
+        for i in 0..self.base.pop_num {
+            if self.base.is_better_at(i, best) {
+                best = i;
+            }
+        }
+        self.base.set_best(best);
+    }
+
+    fn shuffle(&mut self, v: &mut [usize]) {
+        for i in (1..v.len()).rev() {
+            v.swap(i, self.base.rng.int(0, i + 1));
+        }
+    }
+}
+
+fn weight_num(m: usize, h: usize) -> usize {
+    // C(h + m - 1, m - 1)
+    let mut c = 1usize;
+    for i in 1..m {
+        c = c.saturating_mul(h + i) / i;
+    }
+    c
+}
+
+impl<F> Algorithm<F> for MOEAD<F>
+where
+    F: MultiObjFunc,
+{
+    type Setting = MOEADSetting;
+
+    fn create(func: F, settings: Self::Setting) -> Self {
+        // The random vectors are distinct from each other and the current subproblem,
+        // so the number of the weight vectors should be enough
+        let v = settings.strategy.vector_num();
+        let m = func.objective_num();
+        assert!(m > 1, "MOEA/D requires at least two objectives");
+        let h = (1..).find(|&h| weight_num(m, h) > v).unwrap();
+        let mut base = AlgorithmBase::new(func, settings.base.pop_at_least(weight_num(m, h)));
+        // The population is the number of the subproblems
+        let h = (h..)
+            .find(|&h| weight_num(m, h + 1) > base.pop_num)
+            .unwrap();
+        let weights = uniform_weights(m, h);
+        base.pop_num = weights.nrows();
+        base.pool = Array2::zeros((base.pop_num, base.dim));
+        base.fitness = Array1::zeros(base.pop_num);
+        base.violation = Array1::zeros(base.pop_num);
+        Self {
+            decomposition: settings.decomposition,
+            neighbor: settings.neighbor,
+            delta: settings.delta,
+            replace: settings.replace,
+            v: vec![0; v],
+            strategy: settings.strategy,
+            f: settings.f,
+            cross: settings.cross,
+            tmp: Array1::zeros(base.dim),
+            weights,
+            neighbors: Vec::new(),
+            ideal: Array1::zeros(0),
+            objectives: Array2::zeros((0, 0)),
+            base,
+        }
+    }
+
+    #[inline(always)]
+    fn base(&self) -> &AlgorithmBase<F> {
+        &self.base
+    }
+
+    #[inline(always)]
+    fn base_mut(&mut self) -> &mut AlgorithmBase<F> {
+        &mut self.base
+    }
+
+    fn init_pop(&mut self) {
+        for i in 0..self.base.pop_num {
+            for s in 0..self.base.dim {
+                self.base.pool[[i, s]] = self.base.random(s);
+            }
+        }
+        let (objectives, violation) = self.base.evaluate_objectives(self.base.pool.view());
+        let pop_num = self.base.pop_num;
+        let m = objectives.ncols();
+        self.base.violation = violation;
+        self.objectives = objectives;
+        self.ideal = Array1::from_elem(m, f64::INFINITY);
+        for i in 0..pop_num {
+            let f = self.objectives.slice(s![i, ..]).to_owned();
+            self.update_ideal(f.view());
+        }
+        // The nearest weight vectors are the neighbors, including itself
+        let size = self.neighbor.clamp(1, pop_num);
+        self.neighbors = (0..pop_num)
+            .map(|i| {
+                let w = self.weights.slice(s![i, ..]);
+                let dist = self
+                    .weights
+                    .outer_iter()
+                    .map(|u| (&u - &w).mapv(|d| d * d).sum())
+                    .collect::<Vec<_>>();
+                let mut index = (0..pop_num).collect::<Vec<_>>();
+                index.sort_by(|&a, &b| dist[a].total_cmp(&dist[b]));
+                index.truncate(size);
+                index
+            })
+            .collect();
+        self.update_best();
+    }
+
+    fn generation(&mut self) {
+        let pop_num = self.base.pop_num;
+        let all = (0..pop_num).collect::<Vec<_>>();
+        let mut mating = Vec::with_capacity(pop_num);
+        let mut index = Vec::with_capacity(pop_num);
+        let mut child = Array2::zeros((pop_num, self.base.dim));
+        for i in 0..pop_num {
+            let pool = if self.neighbors[i].len() > self.v.len() && self.base.rng.maybe(self.delta)
+            {
+                self.neighbors[i].clone()
+            } else {
+                all.clone()
+            };
+            vector(&mut self.base.rng, i, &pool, &mut self.v);
+            self.tmp.assign(&self.base.pool.slice(s![i, ..]));
+            Recombination {
+                strategy: &self.strategy,
+                f: self.f,
+                // The recombination mutates the variable if the probability is not hit
+                cross: 1. - self.cross,
+                jitter: 0.,
+                pool: self.base.pool.view(),
+                fitness: self.base.fitness.view(),
+                best: self.base.pool.slice(s![i, ..]),
+                v: &self.v,
+                archive: None,
+            }
+            .apply(&mut self.base.rng, &mut self.tmp);
+            for s in 0..self.base.dim {
+                self.tmp[s] = self.check(s, self.tmp[s], self.base.pool[[i, s]]);
+            }
+            if self.base.is_discarded(&self.tmp) {
+                continue;
+            }
+            child.slice_mut(s![i, ..]).assign(&self.tmp);
+            index.push(i);
+            mating.push(pool);
+        }
+        let (objectives, violation) = self
+            .base
+            .evaluate_objectives(child.select(Axis(0), &index).view());
+        for (n, (&i, mut pool)) in index.iter().zip(mating).enumerate() {
+            let f = objectives.slice(s![n, ..]);
+            self.update_ideal(f);
+            self.shuffle(&mut pool);
+            let mut replaced = 0;
+            for j in pool {
+                if replaced >= self.replace {
+                    break;
+                }
+                let g_new = (self.scalarize(f, j), violation[n]);
+                let g_old = (
+                    self.scalarize(self.objectives.slice(s![j, ..]), j),
+                    self.base.violation[j],
+                );
+                if self.base.is_better_than(g_new, g_old) {
+                    self.base
+                        .pool
+                        .slice_mut(s![j, ..])
+                        .assign(&child.slice(s![i, ..]));
+                    self.objectives.slice_mut(s![j, ..]).assign(&f);
+                    self.base.violation[j] = violation[n];
+                    replaced += 1;
+                }
+            }
+        }
+        self.update_best();
+    }
+}
+
+impl<F> MultiObjSolver<F> for MOEAD<F>
+where
+    F: MultiObjFunc,
+{
+    fn pareto_front(&self) -> (Array2<f64>, Array2<f64>) {
+        let b = &self.base;
+        let fronts = non_dominated_sort(b.pop_num, |i, j| {
+            b.constrained_dominates(
+                (self.objectives.slice(s![i, ..]), b.violation[i]),
+                (self.objectives.slice(s![j, ..]), b.violation[j]),
+            )
+        });
+        let index = fronts.into_iter().next().unwrap_or_default();
+        (
+            b.pool.select(Axis(0), &index),
+            self.objectives.select(Axis(0), &index),
+        )
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{multi_obj::*, *};
+use ndarray::{concatenate, s, Array1, Array2, Axis};
+
+setting_builder! {
+    /// Non-dominated Sorting Genetic Algorithm II settings.
+    pub struct NSGA2Setting {
+        @base,
+        @pop_num = 100,
+        /// Crossing probability.
+        cross: f64 = 0.9,
+        /// Mutation probability of each variable, use zero to choose `1 / dim`.
+        mutate: f64 = 0.,
+        /// Distribution index of the simulated binary crossover.
+        eta_c: f64 = 20.,
+        /// Distribution index of the polynomial mutation.
+        eta_m: f64 = 20.,
+    }
+}
+
+/// Non-dominated Sorting Genetic Algorithm II type.
+///
+/// The fitness of the individuals is their non-domination rank,
+/// so the best fitness of the reports is always zero.
+/// Please use [`Task::MaxGen`] or [`Task::MaxTime`] as the termination condition,
+/// and get the result from [`MultiObjSolver::pareto_front`].
+pub struct NSGA2<F: MultiObjFunc> {
+    cross: f64,
+    mutate: f64,
+    crossover: SBX,
+    mutation: Polynomial,
+    objectives: Array2<f64>,
+    rank: Vec<usize>,
+    crowding: Vec<f64>,
+    base: AlgorithmBase<F>,
+}
+
+impl<F> NSGA2<F>
+where
+    F: MultiObjFunc,
+{
+    /// Select `pop_num` survivors by the non-domination ranks and the crowding distances.
+    fn select_survivors(
+        &mut self,
+        pool: Array2<f64>,
+        objectives: Array2<f64>,
+        violation: Array1<f64>,
+    ) {
+        let b = &self.base;
+        let fronts = non_dominated_sort(pool.nrows(), |i, j| {
+            b.constrained_dominates(
+                (objectives.row(i), violation[i]),
+                (objectives.row(j), violation[j]),
+            )
+        });
+        let mut index = Vec::with_capacity(b.pop_num);
+        let mut rank = Vec::with_capacity(b.pop_num);
+        let mut crowding = Vec::with_capacity(b.pop_num);
+        for (r, front) in fronts.iter().enumerate() {
+            if index.len() >= b.pop_num {
+                break;
+            }
+            let dist = crowding_distance(objectives.view(), front);
+            let mut order = (0..front.len()).collect::<Vec<_>>();
+            if index.len() + front.len() > b.pop_num {
+                order.sort_by(|&i, &j| dist[j].total_cmp(&dist[i]));
+                order.truncate(b.pop_num - index.len());
+            }
+            for i in order {
+                index.push(front[i]);
+                rank.push(r);
+                crowding.push(dist[i]);
+            }
+        }
+        self.base.pool = pool.select(Axis(0), &index);
+        self.base.violation = violation.select(Axis(0), &index);
+        self.base.fitness = rank.iter().map(|&r| r as f64).collect();
+        self.objectives = objectives.select(Axis(0), &index);
+        self.rank = rank;
+        self.crowding = crowding;
+        // The first individual is on the first front
+        self.base.set_best(0);
+    }
+
+    fn tournament(&mut self) -> usize {
+        let i = self.base.rng.int(0, self.base.pop_num);
+        let j = self.base.rng.int(0, self.base.pop_num);
+        if self.rank[i] < self.rank[j]
+            || (self.rank[i] == self.rank[j] && self.crowding[i] > self.crowding[j])
+        {
+            i
+        } else {
+            j
+        }
+    }
+}
+
+impl<F> Algorithm<F> for NSGA2<F>
+where
+    F: MultiObjFunc,
+{
+    type Setting = NSGA2Setting;
+
+    fn create(func: F, settings: Self::Setting) -> Self {
+        let base = AlgorithmBase::new(func, settings.base);
+        Self {
+            cross: settings.cross,
+            mutate: settings.mutate,
+            crossover: SBX(settings.eta_c),
+            mutation: Polynomial(settings.eta_m),
+            objectives: Array2::zeros((base.pop_num, base.func.objective_num())),
+            rank: vec![0; base.pop_num],
+            crowding: vec![0.; base.pop_num],
+            base,
+        }
+    }
+
+    #[inline(always)]
+    fn base(&self) -> &AlgorithmBase<F> {
+        &self.base
+    }
+
+    #[inline(always)]
+    fn base_mut(&mut self) -> &mut AlgorithmBase<F> {
+        &mut self.base
+    }
+
+    fn init_pop(&mut self) {
+        let mut pool = Array2::zeros((self.base.pop_num, self.base.dim));
+        for i in 0..self.base.pop_num {
+            for s in 0..self.base.dim {
+                pool[[i, s]] = self.base.random(s);
+            }
+        }
+        let (objectives, violation) = self.base.evaluate_objectives(pool.view());
+        self.select_survivors(pool, objectives, violation);
+    }
+
+    fn generation(&mut self) {
+        let pop_num = self.base.pop_num;
+        let mutate = if self.mutate > 0. {
+            self.mutate
+        } else {
+            1. / self.base.dim as f64
+        };
+        let progress = self.base.progress();
+        let mut child = Array2::zeros((pop_num, self.base.dim));
+        let mut parent = Vec::with_capacity(pop_num);
+        for i in (0..pop_num).step_by(2) {
+            let p1 = self.tournament();
+            let p2 = self.tournament();
+            let (x1, x2) = (self.base.pool.row(p1), self.base.pool.row(p2));
+            let (c1, c2) = if self.base.rng.maybe(self.cross) {
+                self.crossover.cross(&mut self.base.rng, x1, x2)
+            } else {
+                (x1.to_owned(), x2.to_owned())
+            };
+            child.slice_mut(s![i, ..]).assign(&c1);
+            if i + 1 < pop_num {
+                child.slice_mut(s![i + 1, ..]).assign(&c2);
+            }
+            parent.push(p1);
+            if i + 1 < pop_num {
+                parent.push(p2);
+            }
+        }
+        // The discarded children are replaced by their parents
+        let mut objectives = Array2::zeros(self.objectives.raw_dim());
+        let mut violation = Array1::zeros(pop_num);
+        let mut index = Vec::with_capacity(pop_num);
+        for (i, &p) in parent.iter().enumerate() {
+            for s in 0..self.base.dim {
+                let mut v = child[[i, s]];
+                if self.base.rng.maybe(mutate) {
+                    let (lb, ub) = (self.lb(s), self.ub(s));
+                    v = self
+                        .mutation
+                        .mutate(&mut self.base.rng, v, lb, ub, progress);
+                }
+                child[[i, s]] = self.check(s, v, self.base.pool[[p, s]]);
+            }
+            if self.base.is_discarded(child.slice(s![i, ..])) {
+                child
+                    .slice_mut(s![i, ..])
+                    .assign(&self.base.pool.slice(s![p, ..]));
+                objectives
+                    .slice_mut(s![i, ..])
+                    .assign(&self.objectives.slice(s![p, ..]));
+                violation[i] = self.base.violation[p];
+            } else {
+                index.push(i);
+            }
+        }
+        let (f, v) = self
+            .base
+            .evaluate_objectives(child.select(Axis(0), &index).view());
+        for (n, &i) in index.iter().enumerate() {
+            objectives.slice_mut(s![i, ..]).assign(&f.slice(s![n, ..]));
+            violation[i] = v[n];
+        }
+        let pool = concatenate![Axis(0), self.base.pool, child];
+        let objectives = concatenate![Axis(0), self.objectives, objectives];
+        let violation = concatenate![Axis(0), self.base.violation, violation];
+        self.select_survivors(pool, objectives, violation);
+    }
+}
+
+impl<F> MultiObjSolver<F> for NSGA2<F>
+where
+    F: MultiObjFunc,
+{
+    fn pareto_front(&self) -> (Array2<f64>, Array2<f64>) {
+        let index = (0..self.base.pop_num)
+            .filter(|&i| self.rank[i] == 0)
+            .collect::<Vec<_>>();
+        (
+            self.base.pool.select(Axis(0), &index),
+            self.objectives.select(Axis(0), &index),
+        )
+    }
+}
@@ -0,0 +1,338 @@
+//! The genetic operators of [`RGA`].
+use crate::*;
+use ndarray::{Array1, ArrayView1};
+
+/// The population information of the [`Selection`] operators.
+pub struct Population<'a> {
+    /// Fitness of the individuals.
+    pub fitness: ArrayView1<'a, f64>,
+    /// Constraint violation of the individuals.
+    pub violation: ArrayView1<'a, f64>,
+    /// The rank of the individuals, zero is the best.
+    /// See [`AlgorithmBase::ranking`].
+    pub rank: &'a [usize],
+}
+
+impl Population<'_> {
+    /// The fitness-proportional weights of the minimization.
+    ///
+    /// The weight of the feasible individuals is *f_max - f + eps*,
+    /// the infeasible individuals and the non-finite fitness values have no chance.
+    /// Return equal weights if no individual has a chance.
+    fn weights(&self) -> Vec<f64> {
+        let valid = |i: usize| self.violation[i] <= 0. && self.fitness[i].is_finite();
+        let n = self.rank.len();
+        let f_max = (0..n)
+            .filter(|&i| valid(i))
+            .map(|i| self.fitness[i])
+            .fold(f64::NEG_INFINITY, f64::max);
+        let w = (0..n)
+            .map(|i| {
+                if valid(i) {
+                    f_max - self.fitness[i] + f64::EPSILON
+                } else {
+                    0.
+                }
+            })
+            .collect::<Vec<_>>();
+        if w.iter().sum::<f64>() > 0. {
+            w
+        } else {
+            vec![1.; n]
+        }
+    }
+}
+
+/// Pick the index from the cumulative weights by the value between [0, total).
+fn pick(cumulative: &[f64], r: f64) -> usize {
+    cumulative
+        .iter()
+        .position(|&c| r < c)
+        .unwrap_or(cumulative.len() - 1)
+}
+
+fn cumulative(w: &[f64]) -> Vec<f64> {
+    w.iter()
+        .scan(0., |sum, &w| {
+            *sum += w;
+            Some(*sum)
+        })
+        .collect()
+}
+
+/// The selection operator, choose the parents from the population.
+pub trait Selection: Send + Sync {
+    /// Return the indices of `n` chosen individuals, the same individual can be chosen many times.
+    fn select(&self, rng: &mut Rng, pop: &Population, n: usize) -> Vec<usize>;
+}
+
+/// The crossover operator, recombine two parents into two children.
+pub trait Crossover: Send + Sync {
+    /// Return two children of the parents `a` and `b`.
+    ///
+    /// The children will be repaired by the [`BoundaryHandling`] strategy.
+    fn cross(
+        &self,
+        rng: &mut Rng,
+        a: ArrayView1<f64>,
+        b: ArrayView1<f64>,
+    ) -> (Array1<f64>, Array1<f64>);
+}
+
+/// The mutation operator, perturb a variable.
+pub trait Mutation: Send + Sync {
+    /// Return the mutated value of `v` between the bounds [`lb`, `ub`].
+    ///
+    /// The `progress` is the progress of the task between [0, 1],
+    /// see [`AlgorithmBase::progress`].
+    fn mutate(&self, rng: &mut Rng, v: f64, lb: f64, ub: f64, progress: f64) -> f64;
+}
+
+/// Tournament selection, the best of `k` random individuals wins.
+pub struct Tournament(pub usize);
+
+impl Selection for Tournament {
+    fn select(&self, rng: &mut Rng, pop: &Population, n: usize) -> Vec<usize> {
+        let size = pop.rank.len();
+        (0..n)
+            .map(|_| {
+                let mut best = rng.int(0, size);
+                for _ in 1..self.0 {
+                    let i = rng.int(0, size);
+                    if pop.rank[i] < pop.rank[best] {
+                        best = i;
+                    }
+                }
+                best
+            })
+            .collect()
+    }
+}
+
+/// Binary tournament selection, the better of two random individuals wins with the probability,
+/// otherwise the first one is chosen.
+///
+/// This is the selection of the previous RGA with the `win` factor.
+pub struct BinaryTournament(pub f64);
+
+impl Selection for BinaryTournament {
+    fn select(&self, rng: &mut Rng, pop: &Population, n: usize) -> Vec<usize> {
+        let size = pop.rank.len();
+        (0..n)
+            .map(|_| {
+                let i = rng.int(0, size);
+                let j = rng.int(0, size);
+                if pop.rank[j] < pop.rank[i] && rng.maybe(self.0) {
+                    j
+                } else {
+                    i
+                }
+            })
+            .collect()
+    }
+}
+
+/// Roulette wheel selection, the chance is proportional to the fitness.
+///
+/// The fitness is converted to the weight by *f_max - f*,
+/// the infeasible individuals and the non-finite fitness values have no chance.
+pub struct Roulette;
+
+impl Selection for Roulette {
+    fn select(&self, rng: &mut Rng, pop: &Population, n: usize) -> Vec<usize> {
+        let c = cumulative(&pop.weights());
+        let total = c[c.len() - 1];
+        (0..n).map(|_| pick(&c, rng.float(0., total))).collect()
+    }
+}
+
+/// Linear ranking selection with the selection pressure between [1, 2].
+///
+/// The best individual has the weight of the pressure,
+/// and the worst individual has the weight of *2 - pressure*.
+pub struct Rank(pub f64);
+
+impl Selection for Rank {
+    fn select(&self, rng: &mut Rng, pop: &Population, n: usize) -> Vec<usize> {
+        let size = pop.rank.len();
+        let s = self.0.clamp(1., 2.);
+        let w = pop
+            .rank
+            .iter()
+            .map(|&r| {
+                let r = if size > 1 {
+                    (size - 1 - r) as f64 / (size - 1) as f64
+                } else {
+                    1.
+                };
+                2. - s + 2. * (s - 1.) * r
+            })
+            .collect::<Vec<_>>();
+        let c = cumulative(&w);
+        let total = c[c.len() - 1];
+        if total > 0. {
+            (0..n).map(|_| pick(&c, rng.float(0., total))).collect()
+        } else {
+            (0..n).map(|_| rng.int(0, size)).collect()
+        }
+    }
+}
+
+/// Stochastic universal sampling,
+/// the fitness-proportional selection with the equally spaced pointers.
+///
+/// The weights are the same as [`Roulette`].
+pub struct SUS;
+
+impl Selection for SUS {
+    fn select(&self, rng: &mut Rng, pop: &Population, n: usize) -> Vec<usize> {
+        let c = cumulative(&pop.weights());
+        let step = c[c.len() - 1] / n as f64;
+        let start = rng.float(0., step);
+        let mut chosen = (0..n)
+            .map(|i| pick(&c, start + i as f64 * step))
+            .collect::<Vec<_>>();
+        // Shuffle the pointers for the pairing of the crossover
+        for i in (1..n).rev() {
+            chosen.swap(i, rng.int(0, i + 1));
+        }
+        chosen
+    }
+}
+
+/// Simulated binary crossover with the distribution index.
+pub struct SBX(pub f64);
+
+impl Crossover for SBX {
+    fn cross(
+        &self,
+        rng: &mut Rng,
+        a: ArrayView1<f64>,
+        b: ArrayView1<f64>,
+    ) -> (Array1<f64>, Array1<f64>) {
+        let mut c1 = a.to_owned();
+        let mut c2 = b.to_owned();
+        for s in 0..a.len() {
+            if !rng.maybe(0.5) {
+                continue;
+            }
+            let u = rng.rand();
+            let beta = if u <= 0.5 {
+                (2. * u).powf(1. / (self.0 + 1.))
+            } else {
+                (0.5 / (1. - u)).powf(1. / (self.0 + 1.))
+            };
+            c1[s] = 0.5 * ((1. + beta) * a[s] + (1. - beta) * b[s]);
+            c2[s] = 0.5 * ((1. - beta) * a[s] + (1. + beta) * b[s]);
+        }
+        (c1, c2)
+    }
+}
+
+/// Blend crossover, the children are chosen from the interval of the parents,
+/// which is extended by the alpha ratio on each side. (BLX-alpha)
+pub struct BLX(pub f64);
+
+impl Crossover for BLX {
+    fn cross(
+        &self,
+        rng: &mut Rng,
+        a: ArrayView1<f64>,
+        b: ArrayView1<f64>,
+    ) -> (Array1<f64>, Array1<f64>) {
+        let mut c1 = Array1::zeros(a.len());
+        let mut c2 = Array1::zeros(a.len());
+        for s in 0..a.len() {
+            let (lb, ub) = (a[s].min(b[s]), a[s].max(b[s]));
+            let d = self.0 * (ub - lb);
+            let (lb, ub) = (lb - d, ub + d);
+            if lb < ub {
+                c1[s] = rng.float(lb, ub);
+                c2[s] = rng.float(lb, ub);
+            } else {
+                c1[s] = lb;
+                c2[s] = lb;
+            }
+        }
+        (c1, c2)
+    }
+}
+
+/// Arithmetic crossover, the children are the random weighted averages of the parents.
+pub struct Arithmetic;
+
+impl Crossover for Arithmetic {
+    fn cross(
+        &self,
+        rng: &mut Rng,
+        a: ArrayView1<f64>,
+        b: ArrayView1<f64>,
+    ) -> (Array1<f64>, Array1<f64>) {
+        let r = rng.rand();
+        (&a * r + &b * (1. - r), &a * (1. - r) + &b * r)
+    }
+}
+
+/// Uniform crossover, each variable is swapped with the half probability.
+pub struct Uniform;
+
+impl Crossover for Uniform {
+    fn cross(
+        &self,
+        rng: &mut Rng,
+        a: ArrayView1<f64>,
+        b: ArrayView1<f64>,
+    ) -> (Array1<f64>, Array1<f64>) {
+        let mut c1 = a.to_owned();
+        let mut c2 = b.to_owned();
+        for s in 0..a.len() {
+            if rng.maybe(0.5) {
+                std::mem::swap(&mut c1[s], &mut c2[s]);
+            }
+        }
+        (c1, c2)
+    }
+}
+
+/// Polynomial mutation with the distribution index.
+pub struct Polynomial(pub f64);
+
+impl Mutation for Polynomial {
+    fn mutate(&self, rng: &mut Rng, v: f64, lb: f64, ub: f64, _: f64) -> f64 {
+        let u = rng.rand();
+        let delta = if u < 0.5 {
+            (2. * u).powf(1. / (self.0 + 1.)) - 1.
+        } else {
+            1. - (2. * (1. - u)).powf(1. / (self.0 + 1.))
+        };
+        v + delta * (ub - lb)
+    }
+}
+
+/// Gaussian mutation, the standard deviation is the ratio to the range of the bounds.
+pub struct Gaussian(pub f64);
+
+impl Mutation for Gaussian {
+    fn mutate(&self, rng: &mut Rng, v: f64, lb: f64, ub: f64, _: f64) -> f64 {
+        rng.normal(v, self.0 * (ub - lb))
+    }
+}
+
+/// Non-uniform mutation, the step is decreased by the progress with the power.
+///
+/// The step is *y * rand * (1 - progress)^power*,
+/// where *y* is the distance to the upper or lower bound.
+///
+/// This is the mutation of the previous RGA with the `delta` factor.
+pub struct NonUniform(pub f64);
+
+impl Mutation for NonUniform {
+    fn mutate(&self, rng: &mut Rng, v: f64, lb: f64, ub: f64, progress: f64) -> f64 {
+        let k = (1. - progress).powf(self.0);
+        if rng.maybe(0.5) {
+            v + (ub - v) * rng.rand() * k
+        } else {
+            v - (v - lb) * rng.rand() * k
+        }
+    }
+}
@@ -1,6 +1,46 @@
-use ndarray::{s, Array1, Array2};
-
 use crate::*;
+use ndarray::{s, Array1, Array2, Axis};
+
+/// The inertia weight of the velocity.
+///
+/// The decay schedules follow [`AlgorithmBase::progress`].
+#[derive(Clone)]
+pub enum Inertia {
+    /// Fixed inertia weight.
+    Constant(f64),
+    /// Linear decay from the start value to the end value.
+    Linear(f64, f64),
+    /// Non-linear decay from the start value to the end value with the power,
+    /// *end + (start - end) * (1 - t)^power*.
+    NonLinear(f64, f64, f64),
+    /// Clerc's constriction coefficient, which scales the whole velocity by
+    /// *2 / |2 - phi - sqrt(phi^2 - 4 * phi)|*, where *phi* is the sum of the factors.
+    ///
+    /// The sum of the factors should be greater than 4.
+    Constriction,
+}
+
+/// The neighborhood topology of the particles,
+/// the social factor attracts the particle to the best of its neighborhood.
+///
+/// Each particle is always in its own neighborhood.
+#[derive(Clone)]
+pub enum Topology {
+    /// All particles are neighbors, the global best is the attractor.
+    Global,
+    /// The particles are arranged in a ring,
+    /// the neighbors are the `k` particles on each side. (lbest)
+    Ring(usize),
+    /// The particles are arranged row by row in a wrapped grid of *ceil(sqrt(n))* columns,
+    /// the neighbors are the left, right, upper and lower particles.
+    ///
+    /// If the last row is partial,
+    /// each row and column wraps around at its own length.
+    VonNeumann,
+    /// Each particle informs `k` random particles,
+    /// the neighborhoods are redrawn if the global best is not improved in a generation.
+    Random(usize),
+}
 
 setting_builder! {
     /// Particle Swarm Optimization settings.
@@ -11,18 +51,46 @@ setting_builder! {
         cognition: f64 = 2.05,
         /// Social factor.
         social: f64 = 2.05,
-        /// Moving velocity.
-        velocity: f64 = 1.3,
+        /// The inertia weight of the velocity.
+        inertia: Inertia = Inertia::Constriction,
+        /// Maximum velocity of each variable, the ratio to the range of the bounds.
+        /// Use zero to disable the velocity clamping.
+        max_velocity: f64 = 0.5,
+        /// The neighborhood topology of the particles.
+        topology: Topology = Topology::Global,
+        /// Fully informed PSO,
+        /// the particles are attracted by all the personal bests of their neighborhood,
+        /// and the sum of the factors is shared by the neighbors.
+        fully_informed: bool = false,
     }
 }
 
 /// Particle Swarm Optimization type.
+///
+/// The velocity of each particle is the displacement after the boundary handling.
+///
+/// # Breaking Changes
+///
+/// The `velocity` setting of the previous versions is removed,
+/// which multiplied the position instead of a velocity.
+/// Use [`Inertia`] with the `inertia` setting for the weight of the velocity,
+/// and the `max_velocity` setting for the velocity clamping.
+/// The default search is changed to [`Inertia::Constriction`] with the velocity clamping,
+/// use `max_velocity(0.)` to disable the clamping.
 pub struct PSO<F: ObjFunc> {
     cognition: f64,
     social: f64,
-    velocity: f64,
+    inertia: Inertia,
+    max_velocity: f64,
+    topology: Topology,
+    fully_informed: bool,
+    all: Vec<usize>,
+    neighbors: Vec<Vec<usize>>,
+    local: Vec<usize>,
+    velocity: Array2<f64>,
     best_past: Array2<f64>,
     best_f_past: Array1<f64>,
+    best_v_past: Array1<f64>,
     base: AlgorithmBase<F>,
 }
 
@@ -34,7 +102,108 @@ where
         self.best_past
             .slice_mut(s![i, ..])
             .assign(&self.base.pool.slice(s![i, ..]));
-        self.best_f_past[i] = self.base.fitness[i].clone();
+        self.best_f_past[i] = self.base.fitness[i];
+        self.best_v_past[i] = self.base.violation[i];
+    }
+
+    fn neighborhood(&self, i: usize) -> &[usize] {
+        match self.topology {
+            Topology::Global => &self.all,
+            _ => &self.neighbors[i],
+        }
+    }
+
+    fn build_neighbors(&mut self) {
+        let n = self.base.pop_num;
+        let dedup = |mut v: Vec<usize>| {
+            v.sort_unstable();
+            v.dedup();
+            v
+        };
+        let ring = |i: usize, offset: &[usize]| dedup(offset.iter().map(|o| (i + o) % n).collect());
+        self.neighbors = match self.topology {
+            Topology::Global => Vec::new(),
+            Topology::Ring(k) => {
+                let k = k.min(n / 2);
+                let offset = (n - k..n).chain(0..=k).collect::<Vec<_>>();
+                (0..n).map(|i| ring(i, &offset)).collect()
+            }
+            Topology::VonNeumann => {
+                let cols = (n as f64).sqrt().ceil() as usize;
+                let rows = n.div_ceil(cols);
+                // The size of the last row
+                let last = n - (rows - 1) * cols;
+                (0..n)
+                    .map(|i| {
+                        let (r, c) = (i / cols, i % cols);
+                        let w = if r == rows - 1 { last } else { cols };
+                        let h = if c < last { rows } else { rows - 1 };
+                        dedup(vec![
+                            i,
+                            r * cols + (c + 1) % w,
+                            r * cols + (c + w - 1) % w,
+                            (r + 1) % h * cols + c,
+                            (r + h - 1) % h * cols + c,
+                        ])
+                    })
+                    .collect()
+            }
+            Topology::Random(k) => {
+                let mut neighbors = (0..n).map(|i| vec![i]).collect::<Vec<_>>();
+                for i in 0..n {
+                    for _ in 0..k {
+                        neighbors[self.base.rng.int(0, n)].push(i);
+                    }
+                }
+                for v in neighbors.iter_mut() {
+                    v.sort_unstable();
+                    v.dedup();
+                }
+                neighbors
+            }
+        };
+    }
+
+    /// Find the best of the personal bests in each neighborhood.
+    fn update_local(&mut self) {
+        let best_of = |i: usize, neighborhood: &[usize]| {
+            let mut best = i;
+            for &j in neighborhood {
+                let f = (self.best_f_past[j], self.best_v_past[j]);
+                if self
+                    .base
+                    .is_better_than(f, (self.best_f_past[best], self.best_v_past[best]))
+                {
+                    best = j;
+                }
+            }
+            best
+        };
+        if let Topology::Global = self.topology {
+            let best = best_of(0, &self.all);
+            self.local = vec![best; self.base.pop_num];
+            return;
+        }
+        self.local = (0..self.base.pop_num)
+            .map(|i| best_of(i, &self.neighbors[i]))
+            .collect();
+    }
+
+    /// Return the inertia weight and the constriction coefficient.
+    fn weight(&self) -> (f64, f64) {
+        let t = self.base.progress();
+        match self.inertia {
+            Inertia::Constant(w) => (w, 1.),
+            Inertia::Linear(start, end) => (start + (end - start) * t, 1.),
+            Inertia::NonLinear(start, end, power) => {
+                (end + (start - end) * (1. - t).powf(power), 1.)
+            }
+            Inertia::Constriction => {
+                let phi = self.cognition + self.social;
+                let chi = 2. / (2. - phi - (phi * phi - 4. * phi).max(0.).sqrt()).abs();
+                (1., chi.min(1.))
+            }
+        }
     }
 }
 
@@ -49,9 +218,17 @@ where
         Self {
             cognition: settings.cognition,
             social: settings.social,
-            velocity: settings.velocity,
+            inertia: settings.inertia,
+            max_velocity: settings.max_velocity,
+            topology: settings.topology,
+            fully_informed: settings.fully_informed,
+            all: Vec::new(),
+            neighbors: Vec::new(),
+            local: Vec::new(),
+            velocity: Array2::zeros((base.pop_num, base.dim)),
             best_past: Array2::zeros((base.pop_num, base.dim)),
             best_f_past: Array1::zeros(base.pop_num),
+            best_v_past: Array1::zeros(base.pop_num),
             base,
         }
     }
@@ -70,47 +247,81 @@ where
     fn init(&mut self) {
         self.best_past = self.base.pool.clone();
         self.best_f_past = self.base.fitness.clone();
+        self.best_v_past = self.base.violation.clone();
+        self.all = (0..self.base.pop_num).collect();
+        self.build_neighbors();
+        self.update_local();
     }
 
     fn generation(&mut self) {
-        #[cfg(feature = "parallel")]
-        let mut tasks = crate::thread_pool::ThreadPool::new();
+        let (w, chi) = self.weight();
+        let phi = self.cognition + self.social;
+        let mut index = Vec::with_capacity(self.base.pop_num);
+        let mut tmp = Array1::zeros(self.base.dim);
         for i in 0..self.base.pop_num {
-            let alpha = rand!(0., self.cognition);
-            let beta = rand!(0., self.social);
+            let neighborhood = self.neighborhood(i).len() as f64;
             for s in 0..self.base.dim {
-                let v = self.velocity * self.base.pool[[i, s]]
-                    + alpha * (self.best_past[[i, s]] - self.base.pool[[i, s]])
-                    + beta * (self.base.best[s] - self.base.pool[[i, s]]);
-                self.base.pool[[i, s]] = self.check(s, v);
-            }
-            #[cfg(feature = "parallel")]
-            tasks.insert(
-                i,
-                self.base.func.clone(),
-                self.base.report.clone(),
-                self.base.pool.slice(s![i, ..]),
-            );
-            #[cfg(not(feature = "parallel"))]
-            {
-                self.base.fitness(i);
-                if self.base.fitness[i] < self.best_f_past[i] {
-                    self.set_past(i);
+                let x = self.base.pool[[i, s]];
+                let mut v = w * self.velocity[[i, s]];
+                if self.fully_informed {
+                    for n in 0..self.neighborhood(i).len() {
+                        let j = self.neighborhood(i)[n];
+                        let r = self.base.rng.float(0., phi / neighborhood);
+                        v += r * (self.best_past[[j, s]] - x);
+                    }
+                } else {
+                    let alpha = self.base.rng.float(0., self.cognition);
+                    let beta = self.base.rng.float(0., self.social);
+                    let social = match self.topology {
+                        Topology::Global => self.base.best[s],
+                        _ => self.best_past[[self.local[i], s]],
+                    };
+                    v += alpha * (self.best_past[[i, s]] - x);
+                    v += beta * (social - x);
                 }
-                if self.base.fitness[i] < self.base.report.best_f {
-                    self.base.set_best(i);
+                v *= chi;
+                if self.max_velocity > 0. {
+                    let v_max = self.max_velocity * (self.ub(s) - self.lb(s));
+                    v = v.max(-v_max).min(v_max);
                 }
+                tmp[s] = self.check(s, x + v, x);
+            }
+            if self.base.is_discarded(&tmp) {
+                self.velocity.slice_mut(s![i, ..]).fill(0.);
+                continue;
+            }
+            for s in 0..self.base.dim {
+                self.velocity[[i, s]] = tmp[s] - self.base.pool[[i, s]];
             }
+            self.base.pool.slice_mut(s![i, ..]).assign(&tmp);
+            index.push(i);
         }
-        #[cfg(feature = "parallel")]
-        for (i, f) in tasks {
-            self.base.fitness[i] = f;
-            if self.base.fitness[i] < self.best_f_past[i] {
+        let (fitness, violation) = self
+            .base
+            .evaluate(self.base.pool.select(Axis(0), &index).view());
+        for (n, &i) in index.iter().enumerate() {
+            self.base.fitness[i] = fitness[n];
+            self.base.violation[i] = violation[n];
+        }
+        let mut improved = false;
+        for i in index {
+            let f = (self.base.fitness[i], self.base.violation[i]);
+            if self
+                .base
+                .is_better_than(f, (self.best_f_past[i], self.best_v_past[i]))
+            {
                 self.set_past(i);
             }
-            if self.base.fitness[i] < self.base.report.best_f {
+            if self.base.is_best(f.0, f.1) {
                 self.base.set_best(i);
+                improved = true;
+            }
+        }
+        if !improved {
+            if let Topology::Random(_) = self.topology {
+                self.build_neighbors();
             }
         }
+        self.update_local();
     }
 }
@@ -1,30 +1,67 @@
 use crate::*;
-use ndarray::{s, Array1, Array2};
+use ndarray::{s, Array1, Array2, Axis};
+
+/// The replacement policy of the population.
+#[derive(Clone)]
+pub enum Replacement {
+    /// Generational replacement with `k` elites.
+    ///
+    /// The children replace the whole population in each generation,
+    /// then the worst `k` children are replaced by the best `k` individuals of the last generation.
+    Generational(usize),
+    /// Steady-state replacement,
+    /// `n` children are generated in each generation,
+    /// and each child replaces the worst individual of the population unconditionally.
+    ReplaceWorst(usize),
+    /// Steady-state replacement,
+    /// `n` children are generated in each generation,
+    /// and each child replaces its parent only if it is better. (replace-if-better)
+    ReplaceParent(usize),
+}
 
 setting_builder! {
     /// Real-coded Genetic Algorithm settings.
     pub struct RGASetting {
         @base,
         @pop_num = 500,
-        /// Crossing probability.
+        @boundary = BoundaryHandling::Random,
+        /// Crossing probability of each pair.
         cross: f64 = 0.95,
-        /// Mutation probability.
+        /// Mutation probability of each individual, which mutates a random variable.
         mutate: f64 = 0.05,
-        /// Winning probability.
-        win: f64 = 0.95,
-        /// Delta factor.
-        delta: f64 = 5.,
+        /// The selection operator.
+        selection: Box<dyn Selection> = Box::new(Tournament(2)),
+        /// The crossover operator.
+        crossover: Box<dyn Crossover> = Box::new(SBX(2.)),
+        /// The mutation operator.
+        mutation: Box<dyn Mutation> = Box::new(NonUniform(5.)),
+        /// The replacement policy of the population.
+        replacement: Replacement = Replacement::Generational(1),
     }
 }
 
 /// Real-coded Genetic Algorithm type.
+///
+/// The genetic operators are defined by the [`Selection`], [`Crossover`] and [`Mutation`] traits.
+/// The chosen parents are paired in order, and the children inherit the position of their parents.
+/// The children that are not crossed or mutated are not evaluated again.
+///
+/// See [`Replacement`] for how the children join the population.
+///
+/// # Breaking Changes
+///
+/// The `win` and `delta` settings of the previous versions are replaced by the operators,
+/// use [`BinaryTournament`] with `win` and [`NonUniform`] with `delta` for the same selection
+/// and mutation.
+/// The previous crossover is removed, which evaluated three linear combinations of the parents
+/// and kept the best two, since the operators do not evaluate the objective function.
 pub struct RGA<F: ObjFunc> {
     cross: f64,
     mutate: f64,
-    win: f64,
-    delta: f64,
-    new_fitness: Array1<f64>,
-    new_pool: Array2<f64>,
+    selection: Box<dyn Selection>,
+    crossover: Box<dyn Crossover>,
+    mutation: Box<dyn Mutation>,
+    replacement: Replacement,
     base: AlgorithmBase<F>,
 }
 
@@ -32,112 +69,110 @@ impl<F> RGA<F>
 where
     F: ObjFunc,
 {
-    fn crossover(&mut self) {
-        for i in (0..(self.base.pop_num - 1)).step_by(2) {
-            if !maybe!(self.cross) {
-                continue;
-            }
-            let mut tmp = Array2::zeros((3, self.base.dim));
-            let mut f_tmp = Array1::zeros(3);
-            for s in 0..self.base.dim {
-                tmp[[0, s]] = 0.5 * self.base.pool[[i, s]] + 0.5 * self.base.pool[[i + 1, s]];
-                let v = 1.5 * self.base.pool[[i, s]] - 0.5 * self.base.pool[[i + 1, s]];
-                tmp[[1, s]] = self.check(s, v);
-                let v = -0.5 * self.base.pool[[i, s]] + 1.5 * self.base.pool[[i + 1, s]];
-                tmp[[2, s]] = self.check(s, v);
+    /// Generate `n` children, return their parents, variables, fitness and violation.
+    fn offspring(&mut self, n: usize) -> (Vec<usize>, Array2<f64>, Array1<f64>, Array1<f64>) {
+        let mut rank = vec![0; self.base.pop_num];
+        for (r, i) in self.base.ranking().into_iter().enumerate() {
+            rank[i] = r;
+        }
+        let pop = Population {
+            fitness: self.base.fitness.view(),
+            violation: self.base.violation.view(),
+            rank: &rank,
+        };
+        // Choose an even number of parents for the pairs
+        let parents = self.selection.select(&mut self.base.rng, &pop, n + n % 2);
+        let progress = self.base.progress();
+        let mut pool = self.base.pool.select(Axis(0), &parents);
+        let mut fitness = self.base.fitness.select(Axis(0), &parents);
+        let mut violation = self.base.violation.select(Axis(0), &parents);
+        let mut index = Vec::with_capacity(n);
+        for i in (0..n).step_by(2) {
+            let mut changed = [false; 2];
+            if self.base.rng.maybe(self.cross) {
+                let (c1, c2) =
+                    self.crossover
+                        .cross(&mut self.base.rng, pool.row(i), pool.row(i + 1));
+                pool.slice_mut(s![i, ..]).assign(&c1);
+                pool.slice_mut(s![i + 1, ..]).assign(&c2);
+                changed = [true; 2];
             }
-            #[cfg(feature = "parallel")]
-            let mut tasks = crate::thread_pool::ThreadPool::new();
-            for j in 0..3 {
-                #[cfg(feature = "parallel")]
-                tasks.insert(
-                    j,
-                    self.base.func.clone(),
-                    self.base.report.clone(),
-                    tmp.slice(s![j, ..]),
-                );
-                #[cfg(not(feature = "parallel"))]
-                {
-                    f_tmp[j] = self
-                        .base
-                        .func
-                        .fitness(tmp.slice(s![j, ..]), &self.base.report);
+            for (j, changed) in (i..n.min(i + 2)).zip(changed.iter_mut()) {
+                if self.base.rng.maybe(self.mutate) {
+                    let s = self.base.rng.int(0, self.base.dim);
+                    let (lb, ub) = (self.lb(s), self.ub(s));
+                    pool[[j, s]] =
+                        self.mutation
+                            .mutate(&mut self.base.rng, pool[[j, s]], lb, ub, progress);
+                    *changed = true;
                 }
-            }
-            #[cfg(feature = "parallel")]
-            for (j, f) in tasks {
-                f_tmp[j] = f;
-            }
-            if f_tmp[0] > f_tmp[1] {
-                f_tmp.swap(0, 1);
-                for j in 0..2 {
-                    tmp.swap([0, j], [1, j]);
+                if !*changed {
+                    continue;
                 }
-            }
-            if f_tmp[0] > f_tmp[2] {
-                f_tmp.swap(0, 2);
-                for j in 0..2 {
-                    tmp.swap([0, j], [2, j]);
+                let p = parents[j];
+                for s in 0..self.base.dim {
+                    pool[[j, s]] = self.base.check(s, pool[[j, s]], self.base.pool[[p, s]]);
                 }
-            }
-            if f_tmp[1] > f_tmp[2] {
-                f_tmp.swap(1, 2);
-                for j in 0..2 {
-                    tmp.swap([1, j], [2, j]);
+                // The discarded children are replaced by their parents
+                if self.base.is_discarded(pool.slice(s![j, ..])) {
+                    pool.slice_mut(s![j, ..])
+                        .assign(&self.base.pool.slice(s![p, ..]));
+                    continue;
                 }
+                index.push(j);
             }
-            self.assign_from(i, f_tmp[0], &tmp.slice(s![0, ..]).into_owned());
-            self.assign_from(i + 1, f_tmp[1], &tmp.slice(s![1, ..]).into_owned());
         }
+        let (f, v) = self.base.evaluate(pool.select(Axis(0), &index).view());
+        for (n, &j) in index.iter().enumerate() {
+            fitness[j] = f[n];
+            violation[j] = v[n];
+        }
+        let pool = pool.slice(s![..n, ..]).to_owned();
+        let fitness = fitness.slice(s![..n]).to_owned();
+        let violation = violation.slice(s![..n]).to_owned();
+        (parents, pool, fitness, violation)
     }
 
-    fn get_delta(&self, y: f64) -> f64 {
-        let r = match self.base.task {
-            Task::MaxGen(v) if v > 0 => self.base.report.gen as f64 / v as f64,
-            _ => 1.,
-        };
-        y * rand!() * (1. - r).powf(self.delta)
-    }
-
-    fn mutate(&mut self) {
-        for i in 0..self.base.pop_num {
-            if !maybe!(self.mutate) {
-                continue;
+    fn generational(&mut self, k: usize) {
+        let pop_num = self.base.pop_num;
+        let k = k.min(pop_num);
+        let mut elites = self.base.ranking();
+        elites.truncate(k);
+        let b = &self.base;
+        let pool = b.pool.select(Axis(0), &elites);
+        let fitness = b.fitness.select(Axis(0), &elites);
+        let violation = b.violation.select(Axis(0), &elites);
+        let (_, child, child_f, child_v) = self.offspring(pop_num);
+        self.base.pool = child;
+        self.base.fitness = child_f;
+        self.base.violation = child_v;
+        if k > 0 {
+            let worst = self.base.ranking().split_off(pop_num - k);
+            for (n, i) in worst.into_iter().enumerate() {
+                self.assign_from(i, fitness[n], violation[n], pool.slice(s![n, ..]));
             }
-            let s = rand!(0, self.base.dim);
-            if maybe!(0.5) {
-                self.base.pool[[i, s]] += self.get_delta(self.ub(s) - self.base.pool[[i, s]]);
-            } else {
-                self.base.pool[[i, s]] -= self.get_delta(self.base.pool[[i, s]] - self.lb(s));
-            }
-            self.base.fitness(i);
         }
         self.find_best();
     }
 
-    fn select(&mut self) {
-        for i in 0..self.base.pop_num {
-            let j = rand!(0, self.base.pop_num);
-            let k = rand!(0, self.base.pop_num);
-            if self.base.fitness[j] > self.base.fitness[k] && maybe!(self.win) {
-                self.new_fitness[i] = self.base.fitness[k];
-                self.new_pool
-                    .slice_mut(s![i, ..])
-                    .assign(&self.base.pool.slice(s![k, ..]));
+    fn steady_state(&mut self, n: usize, replace_worst: bool) {
+        let (parents, pool, fitness, violation) = self.offspring(n.max(1));
+        for (j, &p) in parents.iter().enumerate().take(pool.nrows()) {
+            let new = (fitness[j], violation[j]);
+            let i = if replace_worst {
+                let b = &self.base;
+                (0..b.pop_num).fold(0, |w, i| if b.is_better_at(w, i) { i } else { w })
+            } else if self
+                .base
+                .is_better_than(new, (self.base.fitness[p], self.base.violation[p]))
+            {
+                p
             } else {
-                self.new_fitness[i] = self.base.fitness[j];
-                self.new_pool
-                    .slice_mut(s![i, ..])
-                    .assign(&self.base.pool.slice(s![j, ..]));
-            }
-            self.base.fitness.assign(&self.new_fitness);
-            self.base.pool.assign(&self.new_pool);
-            self.assign_from(
-                rand!(0, self.base.pop_num),
-                self.base.report.best_f,
-                &self.base.best.clone(),
-            );
+                continue;
+            };
+            self.assign_from(i, new.0, new.1, pool.slice(s![j, ..]));
         }
+        self.find_best();
     }
 }
 
@@ -152,10 +187,10 @@ where
         Self {
             cross: settings.cross,
             mutate: settings.mutate,
-            win: settings.win,
-            delta: settings.delta,
-            new_fitness: Array1::zeros(base.pop_num),
-            new_pool: Array2::zeros((base.pop_num, base.dim)),
+            selection: settings.selection,
+            crossover: settings.crossover,
+            mutation: settings.mutation,
+            replacement: settings.replacement,
             base,
         }
     }
@@ -172,17 +207,10 @@ where
 
     #[inline(always)]
     fn generation(&mut self) {
-        self.select();
-        self.crossover();
-        self.mutate();
-    }
-
-    #[inline(always)]
-    fn check(&self, s: usize, v: f64) -> f64 {
-        if self.ub(s) < v || self.lb(s) > v {
-            rand!(self.lb(s), self.ub(s))
-        } else {
-            v
+        match self.replacement {
+            Replacement::Generational(k) => self.generational(k),
+            Replacement::ReplaceWorst(n) => self.steady_state(n, true),
+            Replacement::ReplaceParent(n) => self.steady_state(n, false),
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::*;
-use ndarray::{s, Array1};
+use ndarray::{s, Array1, Array2, Axis};
 
 /// Teaching Learning Based Optimization settings.
 /// This is a type alias to [`Setting`].
@@ -7,7 +7,7 @@ pub type TLBOSetting = Setting;
 
 /// Teaching Learning Based Optimization type.
 pub struct TLBO<F: ObjFunc> {
-    tmp: Array1<f64>,
+    tmp: Array2<f64>,
     base: AlgorithmBase<F>,
 }
 
@@ -15,51 +15,72 @@ impl<F> TLBO<F>
 where
     F: ObjFunc,
 {
-    fn register(&mut self, i: usize) {
-        let f_new = self.base.func.fitness(&self.tmp, &self.base.report);
-        if f_new < self.base.fitness[i] {
-            self.base.pool.slice_mut(s![i, ..]).assign(&self.tmp);
-            self.base.fitness[i] = f_new;
-        }
-        if f_new < self.base.report.best_f {
-            self.base.set_best(i);
+    fn register(&mut self) {
+        let index = (0..self.base.pop_num)
+            .filter(|&i| !self.base.is_discarded(self.tmp.slice(s![i, ..])))
+            .collect::<Vec<_>>();
+        let (fitness, violation) = self.base.evaluate(self.tmp.select(Axis(0), &index).view());
+        for (n, &i) in index.iter().enumerate() {
+            let (f_new, v_new) = (fitness[n], violation[n]);
+            if self.base.is_better_than(
+                (f_new, v_new),
+                (self.base.fitness[i], self.base.violation[i]),
+            ) {
+                self.base
+                    .pool
+                    .slice_mut(s![i, ..])
+                    .assign(&self.tmp.slice(s![i, ..]));
+                self.base.fitness[i] = f_new;
+                self.base.violation[i] = v_new;
+            }
+            if self.base.is_best(f_new, v_new) {
+                self.base.set_best(i);
+            }
         }
     }
 
-    fn teaching(&mut self, i: usize) {
-        let tf = f64::round(rand!() + 1.);
+    fn teaching(&mut self) {
+        let mut mean = Array1::<f64>::zeros(self.base.dim);
         for s in 0..self.base.dim {
-            let mut mean = 0.;
             for j in 0..self.base.pop_num {
-                mean += self.base.pool[[j, s]];
+                mean[s] += self.base.pool[[j, s]];
+            }
+            mean[s] /= self.base.dim as f64;
+        }
+        for i in 0..self.base.pop_num {
+            let tf = f64::round(self.base.rng.rand() + 1.);
+            for s in 0..self.base.dim {
+                let v = self.base.pool[[i, s]]
+                    + self.base.rng.float(1., self.base.dim as f64)
+                        * (self.base.best[s] - tf * mean[s]);
+                self.tmp[[i, s]] = self.check(s, v, self.base.pool[[i, s]]);
             }
-            mean /= self.base.dim as f64;
-            let v = self.base.pool[[i, s]]
-                + rand!(1., self.base.dim as f64) * (self.base.best[s] - tf * mean);
-            self.tmp[s] = self.check(s, v);
         }
-        self.register(i);
+        self.register();
     }
 
-    fn learning(&mut self, i: usize) {
-        let j = {
-            let j = rand!(0, self.base.pop_num - 1);
-            if j >= i {
-                j + 1
-            } else {
-                j
-            }
-        };
-        for s in 0..self.base.dim {
-            let diff = if self.base.fitness[j] < self.base.fitness[i] {
-                self.base.pool[[i, s]] - self.base.pool[[j, s]]
-            } else {
-                self.base.pool[[j, s]] - self.base.pool[[i, s]]
+    fn learning(&mut self) {
+        for i in 0..self.base.pop_num {
+            let j = {
+                let j = self.base.rng.int(0, self.base.pop_num - 1);
+                if j >= i {
+                    j + 1
+                } else {
+                    j
+                }
             };
-            let v = self.base.pool[[i, s]] + rand!(1., self.base.dim as f64) * diff;
-            self.tmp[s] = self.check(s, v);
+            for s in 0..self.base.dim {
+                let diff = if self.base.is_better_at(j, i) {
+                    self.base.pool[[i, s]] - self.base.pool[[j, s]]
+                } else {
+                    self.base.pool[[j, s]] - self.base.pool[[i, s]]
+                };
+                let v =
+                    self.base.pool[[i, s]] + self.base.rng.float(1., self.base.dim as f64) * diff;
+                self.tmp[[i, s]] = self.check(s, v, self.base.pool[[i, s]]);
+            }
         }
-        self.register(i);
+        self.register();
     }
 }
 
@@ -72,7 +93,7 @@ where
     fn create(func: F, settings: Self::Setting) -> Self {
         let base = AlgorithmBase::new(func, settings);
         Self {
-            tmp: Array1::zeros(base.dim),
+            tmp: Array2::zeros((base.pop_num, base.dim)),
             base,
         }
     }
@@ -89,9 +110,7 @@ where
 
     #[inline(always)]
     fn generation(&mut self) {
-        for i in 0..self.base.pop_num {
-            self.teaching(i);
-            self.learning(i);
-        }
+        self.teaching();
+        self.learning();
     }
 }
@@ -0,0 +1,238 @@
+use crate::{is_better, Algorithm, AlgorithmBase, ObjFunc, Report};
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, AsArray, Axis};
+
+/// The base of the multi-objective function.
+///
+/// The multi-objective function is also an [`ObjFunc`],
+/// which provides the bounds, variables, constraints and the final result.
+/// The single-objective [`ObjFunc::fitness`] can be a scalarization of the objectives,
+/// so the problem can be solved by the single-objective algorithms as well.
+///
+/// ```
+/// use metaheuristics_nature::{MultiObjFunc, ObjFunc, Report};
+/// use ndarray::{Array1, AsArray, ArrayView1};
+///
+/// struct MassStiffness(Array1<f64>, Array1<f64>);
+///
+/// impl ObjFunc for MassStiffness {
+///     type Result = f64;
+///
+///     fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
+///     where
+///         A: AsArray<'a, f64>,
+///     {
+///         self.objectives(v, report).sum()
+///     }
+///
+///     fn result<'a, V>(&self, v: V) -> Self::Result
+///     where
+///         V: AsArray<'a, f64>,
+///     {
+///         self.fitness(v, &Default::default())
+///     }
+///
+///     fn ub(&self) -> ArrayView1<'_, f64> { self.1.view() }
+///     fn lb(&self) -> ArrayView1<'_, f64> { self.0.view() }
+/// }
+///
+/// impl MultiObjFunc for MassStiffness {
+///     fn objectives<'a, A>(&self, v: A, _: &Report) -> Array1<f64>
+///     where
+///         A: AsArray<'a, f64>,
+///     {
+///         let v = v.into();
+///         let mass = v[0] * v[1];
+///         let compliance = 1. / (v[0] * v[1].powi(3));
+///         ndarray::arr1(&[mass, compliance])
+///     }
+///
+///     fn objective_num(&self) -> usize { 2 }
+/// }
+/// ```
+pub trait MultiObjFunc: ObjFunc {
+    /// Return the objective values of the design parameters, all of them are minimized.
+    ///
+    /// The length should be [`MultiObjFunc::objective_num`].
+    fn objectives<'a, A>(&self, v: A, report: &Report) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>;
+
+    /// Return the number of the objectives.
+    fn objective_num(&self) -> usize;
+}
+
+/// Return true if the objectives `a` dominate the objectives `b`.
+///
+/// That is, `a` is not worse than `b` in all objectives and better in at least one objective.
+/// The comparison follows [`is_better`], so the NaN value is treated as the worst.
+/// ```
+/// use metaheuristics_nature::dominates;
+/// use ndarray::arr1;
+///
+/// assert!(dominates(&arr1(&[1., 2.]), &arr1(&[1., 3.])));
+/// assert!(!dominates(&arr1(&[1., 2.]), &arr1(&[2., 1.])));
+/// assert!(!dominates(&arr1(&[1., 2.]), &arr1(&[1., 2.])));
+/// ```
+pub fn dominates<'a, A>(a: A, b: A) -> bool
+where
+    A: AsArray<'a, f64>,
+{
+    let a = a.into();
+    let b = b.into();
+    let mut better = false;
+    for (&a, &b) in a.iter().zip(b.iter()) {
+        if is_better(b, a) {
+            return false;
+        }
+        better |= is_better(a, b);
+    }
+    better
+}
+
+/// Generate the uniform weight vectors on the simplex of `m` objectives,
+/// each objective is divided into `h` parts. (Das and Dennis's method)
+///
+/// The number of the weight vectors is *C(h + m - 1, m - 1)*.
+/// ```
+/// use metaheuristics_nature::uniform_weights;
+///
+/// let w = uniform_weights(3, 2);
+/// assert_eq!(w.nrows(), 6);
+/// assert!(w.outer_iter().all(|w| (w.sum() - 1.).abs() < 1e-12));
+/// ```
+pub fn uniform_weights(m: usize, h: usize) -> Array2<f64> {
+    fn fill(w: &mut Vec<f64>, current: &mut [usize], k: usize, left: usize, h: usize) {
+        if k == current.len() - 1 {
+            current[k] = left;
+            w.extend(current.iter().map(|&c| c as f64 / h as f64));
+            return;
+        }
+        for c in 0..=left {
+            current[k] = c;
+            fill(w, current, k + 1, left - c, h);
+        }
+    }
+    let mut w = Vec::new();
+    fill(&mut w, &mut vec![0; m], 0, h, h);
+    Array2::from_shape_vec((w.len() / m, m), w).unwrap()
+}
+
+/// Sort the individuals into the non-dominated fronts,
+/// `dominate(i, j)` returns true if the individual `i` dominates the individual `j`.
+pub(crate) fn non_dominated_sort(
+    n: usize,
+    dominate: impl Fn(usize, usize) -> bool,
+) -> Vec<Vec<usize>> {
+    let mut dominated = vec![Vec::new(); n];
+    let mut count = vec![0; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            if dominate(i, j) {
+                dominated[i].push(j);
+                count[j] += 1;
+            } else if dominate(j, i) {
+                dominated[j].push(i);
+                count[i] += 1;
+            }
+        }
+    }
+    let mut front = (0..n).filter(|&i| count[i] == 0).collect::<Vec<_>>();
+    let mut fronts = Vec::new();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &i in &front {
+            for &j in &dominated[i] {
+                count[j] -= 1;
+                if count[j] == 0 {
+                    next.push(j);
+                }
+            }
+        }
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+/// Return the crowding distances of the front, each row of `objectives` is an individual.
+///
+/// The boundary individuals have the infinite distance.
+pub(crate) fn crowding_distance(objectives: ArrayView2<f64>, front: &[usize]) -> Vec<f64> {
+    let mut dist = vec![0.; front.len()];
+    if front.len() < 3 {
+        dist.iter_mut().for_each(|d| *d = f64::INFINITY);
+        return dist;
+    }
+    let mut order = (0..front.len()).collect::<Vec<_>>();
+    for f in objectives.axis_iter(Axis(1)) {
+        order.sort_by(|&a, &b| f[front[a]].total_cmp(&f[front[b]]));
+        let first = f[front[order[0]]];
+        let last = f[front[order[order.len() - 1]]];
+        dist[order[0]] = f64::INFINITY;
+        dist[order[order.len() - 1]] = f64::INFINITY;
+        let range = last - first;
+        if !range.is_finite() || range <= 0. {
+            continue;
+        }
+        for w in order.windows(3) {
+            dist[w[1]] += (f[front[w[2]]] - f[front[w[0]]]) / range;
+        }
+    }
+    dist
+}
+
+impl<F: MultiObjFunc> AlgorithmBase<F> {
+    /// Get objectives and constraint violation of the candidates by [`MultiObjFunc::objectives`],
+    /// each row is a candidate.
+    ///
+    /// If the `parallel` feature is enabled, the candidates are evaluated by the workers.
+    /// The candidates over the budget of [`crate::Task::MaxEval`] or panicked are treated as
+    /// the worst, as the same as [`AlgorithmBase::evaluate`].
+    pub fn evaluate_objectives(&self, v: ArrayView2<f64>) -> (Array2<f64>, Array1<f64>) {
+        let violation = v.outer_iter().map(|v| self.violation_of(v)).collect();
+        let m = self.func.objective_num();
+        let func = self.func.clone();
+        let report = self.report.clone();
+        let worst = Array1::from_elem(m, f64::INFINITY);
+        let is_nan = |f: &Array1<f64>| f.iter().any(|f| f.is_nan());
+        let ans = self.evaluate_by(v, worst, is_nan, move |v| {
+            v.outer_iter()
+                .map(|v| crate::error::catch(|| Ok(func.objectives(v, &report))))
+                .collect()
+        });
+        let mut objectives = Array2::zeros((v.nrows(), m));
+        for (mut row, f) in objectives.outer_iter_mut().zip(ans) {
+            row.assign(&f);
+        }
+        (objectives, violation)
+    }
+
+    /// Return true if the objectives `a` with violation `va`
+    /// dominate the objectives `b` with violation `vb`.
+    ///
+    /// The infeasible candidates are compared with their violations first,
+    /// see [`AlgorithmBase::cmp_violation`].
+    pub fn constrained_dominates<'a>(
+        &self,
+        (a, va): (ArrayView1<'a, f64>, f64),
+        (b, vb): (ArrayView1<'a, f64>, f64),
+    ) -> bool {
+        self.cmp_violation(va, vb)
+            .unwrap_or_else(|| dominates(a, b))
+    }
+}
+
+/// The result API of the multi-objective algorithms.
+pub trait MultiObjSolver<F: MultiObjFunc>: Algorithm<F> {
+    /// Return the design parameters and the objectives of the Pareto front,
+    /// each row is a non-dominated solution.
+    /// The algorithm must be executed once.
+    fn pareto_front(&self) -> (Array2<f64>, Array2<f64>);
+
+    /// Get the results of the Pareto front from the objective function.
+    fn pareto_results(&self) -> Vec<F::Result> {
+        let (x, _) = self.pareto_front();
+        let func = &self.base().func;
+        x.outer_iter().map(|x| func.result(x)).collect()
+    }
+}
@@ -1,5 +1,5 @@
-use crate::Report;
-use ndarray::{ArrayView1, AsArray};
+use crate::{Error, Report, Variable};
+use ndarray::{Array1, ArrayView1, ArrayView2, AsArray};
 
 /// The base of the objective function.
 ///
@@ -32,8 +32,8 @@ use ndarray::{ArrayView1, AsArray};
 ///         self.fitness(v, &Default::default())
 ///     }
 ///
-///     fn ub(&self) -> ArrayView1<f64> { self.1.view() }
-///     fn lb(&self) -> ArrayView1<f64> { self.0.view() }
+///     fn ub(&self) -> ArrayView1<'_, f64> { self.1.view() }
+///     fn lb(&self) -> ArrayView1<'_, f64> { self.0.view() }
 /// }
 /// ```
 /// The objective function returns fitness value that used to evaluate the objective.
@@ -72,18 +72,103 @@ pub trait ObjFunc: Sync + Send + 'static {
     /// So that, we use secondary evaluation function to measure the result from other requirements,
     /// we call it "constraint" or "penalty function".
     /// The penalty value usually multiply a weight factor for increasing its influence.
+    ///
+    /// # Constraints
+    ///
+    /// Instead of the penalty function, the constraints can be provided by
+    /// [`ObjFunc::inequality`] and [`ObjFunc::equality`] without tuning the weight factors.
+    /// The candidates are ranked by the
+    /// [`ConstraintHandling`](crate::ConstraintHandling) of the settings.
     fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
     where
         A: AsArray<'a, f64>;
 
+    /// Return fitness or an error if the evaluation is failed.
+    ///
+    /// The failed evaluation is handled by the [`FailurePolicy`](crate::FailurePolicy)
+    /// of the settings, instead of faking a positive infinity.
+    /// The default implementation calls [`ObjFunc::fitness`].
+    fn try_fitness<'a, A>(&self, v: A, report: &Report) -> Result<f64, Error>
+    where
+        A: AsArray<'a, f64>,
+    {
+        Ok(self.fitness(v, report))
+    }
+
+    /// Set to true if [`ObjFunc::fitness_batch`] is implemented,
+    /// then the candidates are evaluated by it instead of [`ObjFunc::try_fitness`].
+    const BATCH: bool = false;
+
+    /// Return fitness of the candidates, each row is a candidate.
+    ///
+    /// The vectorizable objective function can implement this method and set
+    /// [`ObjFunc::BATCH`], so the candidates of a generation are evaluated at once.
+    /// If the `parallel` feature is enabled, the candidates are split into chunks,
+    /// one for each worker, and this method is called once for each chunk.
+    /// The default implementation calls [`ObjFunc::fitness`] for each row.
+    fn fitness_batch(&self, v: ArrayView2<f64>, report: &Report) -> Array1<f64> {
+        v.outer_iter().map(|v| self.fitness(v, report)).collect()
+    }
+
+    /// Return fitness or an error of the candidates, each row is a candidate.
+    ///
+    /// The algorithms evaluate their candidates through this method,
+    /// with the same chunks as [`ObjFunc::fitness_batch`].
+    /// The default implementation calls [`ObjFunc::fitness_batch`] if [`ObjFunc::BATCH`] is set,
+    /// otherwise calls [`ObjFunc::try_fitness`] for each row,
+    /// and the panic of a row only fails the row itself.
+    fn try_fitness_batch(&self, v: ArrayView2<f64>, report: &Report) -> Vec<Result<f64, Error>> {
+        if Self::BATCH {
+            crate::error::catch_batch(v.nrows(), || {
+                self.fitness_batch(v, report).into_iter().map(Ok).collect()
+            })
+        } else {
+            v.outer_iter()
+                .map(|v| crate::error::catch(|| self.try_fitness(v, report)))
+                .collect()
+        }
+    }
+
+    /// Return the inequality constraints, which are satisfied if *g(x) <= 0*.
+    ///
+    /// The default implementation has no constraint.
+    fn inequality<'a, A>(&self, _v: A) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>,
+    {
+        Array1::zeros(0)
+    }
+
+    /// Return the equality constraints, which are satisfied if *h(x) = 0*.
+    ///
+    /// The tolerance is decided by the `eq_tol` option of the settings.
+    /// The default implementation has no constraint.
+    fn equality<'a, A>(&self, _v: A) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>,
+    {
+        Array1::zeros(0)
+    }
+
+    /// Return the kind of the variable at index `s`.
+    ///
+    /// The default implementation is [`Variable::Continuous`] for all variables.
+    /// The integer and discrete variables are snapped to the valid values before evaluation,
+    /// so there is no need to round them in [`ObjFunc::fitness`].
+    /// The allowed values of [`Variable::Discrete`] should not be empty,
+    /// otherwise the algorithms will panic.
+    fn variable(&self, _s: usize) -> Variable<'_> {
+        Variable::Continuous
+    }
+
     /// Return the final result of the problem.
     fn result<'a, V>(&self, v: V) -> Self::Result
     where
         V: AsArray<'a, f64>;
 
     /// Get upper bound.
-    fn ub(&self) -> ArrayView1<f64>;
+    fn ub(&self) -> ArrayView1<'_, f64>;
 
     /// Get lower bound.
-    fn lb(&self) -> ArrayView1<f64>;
+    fn lb(&self) -> ArrayView1<'_, f64>;
 }
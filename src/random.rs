@@ -0,0 +1,74 @@
+use rand::{rngs::StdRng, Rng as _, SeedableRng};
+
+/// The random number generator of the algorithms.
+///
+/// The generator is created from a seed,
+/// so the same seed will reproduce the same sequence.
+/// ```
+/// use metaheuristics_nature::Rng;
+///
+/// let mut a = Rng::new(Some(0));
+/// let mut b = Rng::new(Some(0));
+/// assert_eq!(a.float(-1., 1.), b.float(-1., 1.));
+/// assert_eq!(a.seed(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Rng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl Rng {
+    /// Create a generator from a seed.
+    /// A random seed will be chosen if the seed is `None`.
+    pub fn new(seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed of this generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Generate a random value between [0., 1.).
+    #[inline(always)]
+    pub fn rand(&mut self) -> f64 {
+        self.rng.gen()
+    }
+
+    /// Generate a random floating point value between [lb, ub).
+    #[inline(always)]
+    pub fn float(&mut self, lb: f64, ub: f64) -> f64 {
+        self.rng.gen_range(lb..ub)
+    }
+
+    /// Generate a random value from the normal distribution. (Box-Muller transform)
+    #[inline(always)]
+    pub fn normal(&mut self, mean: f64, sd: f64) -> f64 {
+        let u1 = 1. - self.rand();
+        let u2 = self.rand();
+        mean + sd * (-2. * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+
+    /// Generate a random value from the Cauchy distribution.
+    #[inline(always)]
+    pub fn cauchy(&mut self, loc: f64, scale: f64) -> f64 {
+        loc + scale * (std::f64::consts::PI * (self.rand() - 0.5)).tan()
+    }
+
+    /// Generate a random index between [lb, ub).
+    #[inline(always)]
+    pub fn int(&mut self, lb: usize, ub: usize) -> usize {
+        self.rng.gen_range(lb..ub)
+    }
+
+    /// Generate a random boolean by positive factor.
+    #[inline(always)]
+    pub fn maybe(&mut self, p: f64) -> bool {
+        self.rng.gen_bool(p)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{ObjFunc, Report, Task};
+use ndarray::Array1;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// The reason of the termination.
+#[derive(Clone, Debug)]
+pub enum Termination {
+    /// The termination condition is met,
+    /// with the fired condition of [`crate::AlgorithmBase::stopped_by`].
+    Task(Task),
+    /// Interrupted by the callback.
+    Callback,
+}
+
+/// The solution of a run, see [`crate::Solver::solution`] and [`crate::Solver::into_solution`].
+///
+/// The solution is independent of the algorithm,
+/// so it can be stored, logged and compared with the other runs.
+/// It is [`Clone`] and [`Debug`] if the result type [`ObjFunc::Result`] is.
+pub struct Solution<F: ObjFunc> {
+    /// The best variables.
+    pub best: Array1<f64>,
+    /// The best fitness.
+    pub best_f: f64,
+    /// The constraint violation of the best.
+    pub violation: f64,
+    /// The result of the best variables, see [`ObjFunc::result`].
+    pub result: F::Result,
+    /// The history reports.
+    pub history: Vec<Report>,
+    /// Number of the objective function evaluations.
+    pub eval: u64,
+    /// Wall time of the run in seconds.
+    pub time: f64,
+    /// The random seed, which reproduces the run with the same settings.
+    pub seed: u64,
+    /// The reason of the termination.
+    pub termination: Termination,
+}
+
+impl<F: ObjFunc> Clone for Solution<F>
+where
+    F::Result: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            best: self.best.clone(),
+            best_f: self.best_f,
+            violation: self.violation,
+            result: self.result.clone(),
+            history: self.history.clone(),
+            eval: self.eval,
+            time: self.time,
+            seed: self.seed,
+            termination: self.termination.clone(),
+        }
+    }
+}
+
+impl<F: ObjFunc> Debug for Solution<F>
+where
+    F::Result: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("Solution")
+            .field("best", &self.best)
+            .field("best_f", &self.best_f)
+            .field("violation", &self.violation)
+            .field("result", &self.result)
+            .field("history", &self.history)
+            .field("eval", &self.eval)
+            .field("time", &self.time)
+            .field("seed", &self.seed)
+            .field("termination", &self.termination)
+            .finish()
+    }
+}
@@ -1,5 +1,6 @@
 use crate::*;
-use ndarray::{Array1, ArrayView1, AsArray};
+use ndarray::{Array1, ArrayView1, ArrayView2, AsArray};
+use std::sync::atomic::{AtomicU32, Ordering};
 
 struct TestObj(Array1<f64>, Array1<f64>);
 
@@ -28,14 +29,341 @@ impl ObjFunc for TestObj {
         self.fitness(v, &Default::default())
     }
 
-    fn ub(&self) -> ArrayView1<f64> {
+    fn ub(&self) -> ArrayView1<'_, f64> {
         self.1.view()
     }
-    fn lb(&self) -> ArrayView1<f64> {
+    fn lb(&self) -> ArrayView1<'_, f64> {
         self.0.view()
     }
 }
 
+/// Count the batches and the candidates.
+#[derive(Default)]
+struct BatchObj(TestObj, AtomicU32, AtomicU32);
+
+impl ObjFunc for BatchObj {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, _: A, _: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        unreachable!()
+    }
+
+    const BATCH: bool = true;
+
+    fn fitness_batch(&self, v: ArrayView2<f64>, report: &Report) -> Array1<f64> {
+        self.1.fetch_add(1, Ordering::Relaxed);
+        self.2.fetch_add(v.nrows() as u32, Ordering::Relaxed);
+        v.outer_iter().map(|v| self.0.fitness(v, report)).collect()
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.0.result(v)
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.0.ub()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.0.lb()
+    }
+}
+
+/// Fail on the upper half of the first variable and the first N calls,
+/// or panic on the upper half instead.
+#[derive(Default)]
+struct FailObj {
+    obj: TestObj,
+    calls: AtomicU32,
+    fail_first: u32,
+    panic: bool,
+}
+
+impl ObjFunc for FailObj {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        self.try_fitness(v, report).unwrap_or(f64::INFINITY)
+    }
+
+    fn try_fitness<'a, A>(&self, v: A, report: &Report) -> Result<f64, Error>
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        if self.calls.fetch_add(1, Ordering::Relaxed) < self.fail_first {
+            Err(Error::objective("not ready"))
+        } else if v[0] > 25. && self.panic {
+            panic!("simulator crashed");
+        } else if v[0] > 25. {
+            Err(Error::objective("failed to converge"))
+        } else {
+            Ok(self.obj.fitness(v, report))
+        }
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.obj.result(v)
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.obj.ub()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.obj.lb()
+    }
+}
+
+/// Return NaN on the upper half of the first variable.
+#[derive(Default)]
+struct NaNObj(TestObj);
+
+impl ObjFunc for NaNObj {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        if v[0] > 25. {
+            f64::NAN
+        } else {
+            self.0.fitness(v, report)
+        }
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.0.result(v)
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.0.ub()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.0.lb()
+    }
+}
+
+/// Subject to `x0 >= 10` and `x2 == 5`.
+#[derive(Default)]
+struct ConsObj(TestObj);
+
+impl ObjFunc for ConsObj {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        self.0.fitness(v, report)
+    }
+
+    fn inequality<'a, A>(&self, v: A) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        ndarray::arr1(&[10. - v[0]])
+    }
+
+    fn equality<'a, A>(&self, v: A) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        ndarray::arr1(&[v[2] - 5.])
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.0.result(v)
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.0.ub()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.0.lb()
+    }
+}
+
+/// The first variable is an integer and the second variable is a gear size.
+#[derive(Default)]
+struct MixedObj(TestObj);
+
+impl ObjFunc for MixedObj {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, v: A, _: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        (v[0] - 2.4).powi(2) + (v[1] - 8.).powi(2) + v[2] * v[2] + v[3] * v[3]
+    }
+
+    fn variable(&self, s: usize) -> Variable<'_> {
+        match s {
+            0 => Variable::Integer,
+            1 => Variable::Discrete(&[3., 7., 11., 20.]),
+            _ => Variable::Continuous,
+        }
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.fitness(v, &Default::default())
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.0.ub()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.0.lb()
+    }
+}
+
+/// ZDT1 problem, the Pareto front is `f2 = 1 - sqrt(f1)`.
+struct Zdt1(Array1<f64>, Array1<f64>);
+
+impl Default for Zdt1 {
+    fn default() -> Self {
+        Self(Array1::zeros(4), Array1::ones(4))
+    }
+}
+
+impl ObjFunc for Zdt1 {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        self.objectives(v, report).sum()
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.fitness(v, &Default::default())
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.1.view()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.0.view()
+    }
+}
+
+impl MultiObjFunc for Zdt1 {
+    fn objectives<'a, A>(&self, v: A, _: &Report) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        let g = 1. + 9. * v.slice(ndarray::s![1..]).sum() / 3.;
+        ndarray::arr1(&[v[0], g * (1. - (v[0] / g).sqrt())])
+    }
+
+    fn objective_num(&self) -> usize {
+        2
+    }
+}
+
+/// Record the evaluated candidates in order.
+#[derive(Default)]
+struct RecordObj(Zdt1, std::sync::Mutex<Vec<Array1<f64>>>);
+
+impl ObjFunc for RecordObj {
+    type Result = f64;
+
+    fn fitness<'a, A>(&self, v: A, report: &Report) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        self.objectives(v, report).sum()
+    }
+
+    fn result<'a, V>(&self, v: V) -> f64
+    where
+        V: AsArray<'a, f64>,
+    {
+        self.0.result(v)
+    }
+
+    fn ub(&self) -> ArrayView1<'_, f64> {
+        self.0.ub()
+    }
+    fn lb(&self) -> ArrayView1<'_, f64> {
+        self.0.lb()
+    }
+}
+
+impl MultiObjFunc for RecordObj {
+    fn objectives<'a, A>(&self, v: A, report: &Report) -> Array1<f64>
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        self.1.lock().unwrap().push(v.to_owned());
+        self.0.objectives(v, report)
+    }
+
+    fn objective_num(&self) -> usize {
+        self.0.objective_num()
+    }
+}
+
+fn test_pareto<S>(setting: S::Setting)
+where
+    S: MultiObjSolver<Zdt1> + Solver<Zdt1>,
+{
+    let a = S::solve(Zdt1::default(), setting, ());
+    let (x, f) = a.pareto_front();
+    assert_eq!(x.nrows(), f.nrows());
+    assert!(f.nrows() > 10, "{}", f.nrows());
+    // The boundary subproblems may keep the weakly Pareto-optimal solutions
+    let near = f
+        .outer_iter()
+        .filter(|f| (f[1] - (1. - f[0].sqrt())).abs() < 5e-2)
+        .count();
+    assert!(near as f64 >= 0.9 * f.nrows() as f64, "{}", f);
+    let f1 = f.column(0);
+    let spread = f1.fold(0., |a: f64, &b| a.max(b)) - f1.fold(1., |a: f64, &b| a.min(b));
+    assert!(spread > 0.5, "{}", spread);
+    assert_eq!(a.pareto_results().len(), x.nrows());
+}
+
+fn test_seed_pareto<S>(setting: fn() -> S::Setting)
+where
+    S: MultiObjSolver<Zdt1> + Solver<Zdt1>,
+{
+    let a = S::solve(Zdt1::default(), setting(), ());
+    let b = S::solve(Zdt1::default(), setting(), ());
+    assert_eq!(a.pareto_front(), b.pareto_front());
+}
+
 fn test<S>(obj: TestObj, setting: S::Setting)
 where
     S: Solver<TestObj>,
@@ -44,7 +372,7 @@ where
     let ans = a.result();
     let (x, y) = a.parameters();
     let history = a.history();
-    assert!(history.len() > 0, "{}", history.len());
+    assert!(!history.is_empty(), "{}", history.len());
     assert!(ans.abs() < 1e-20, "{}", ans);
     for i in 0..4 {
         assert!(x[i].abs() < 1e-10, "x{} = {}", i, x[i]);
@@ -52,12 +380,87 @@ where
     assert_eq!(y.abs(), ans);
 }
 
+fn test_seed<S>(setting: fn() -> S::Setting)
+where
+    S: Solver<TestObj>,
+{
+    let a = S::solve(TestObj::default(), setting(), ());
+    let b = S::solve(TestObj::default(), setting(), ());
+    assert_eq!(a.parameters(), b.parameters());
+    let history = a.history().into_iter().map(|r| (r.gen, r.best_f));
+    assert!(history.eq(b.history().into_iter().map(|r| (r.gen, r.best_f))));
+}
+
+fn test_threads<S>(setting: fn(usize) -> S::Setting)
+where
+    S: Solver<TestObj>,
+{
+    let a = S::solve(TestObj::default(), setting(1), ());
+    let b = S::solve(TestObj::default(), setting(4), ());
+    assert_eq!(a.parameters(), b.parameters());
+    let history = a.history().into_iter().map(|r| (r.gen, r.best_f));
+    assert!(history.eq(b.history().into_iter().map(|r| (r.gen, r.best_f))));
+}
+
+fn test_eval<S>(setting: S::Setting, budget: u64)
+where
+    S: Solver<BatchObj>,
+{
+    let a = S::solve(BatchObj::default(), setting, ());
+    assert_eq!(a.base().func.2.load(Ordering::Relaxed) as u64, budget);
+    assert_eq!(a.base().report.eval, budget);
+    assert!(a.result().is_finite());
+}
+
+fn test_nan<S>(setting: S::Setting)
+where
+    S: Solver<NaNObj>,
+{
+    let a = S::solve(NaNObj::default(), setting, ());
+    let (x, y) = a.parameters();
+    assert!(x[0] <= 25. && !y.is_nan(), "{} {}", x[0], y);
+    let history = a.history();
+    assert!(history.iter().all(|r| !r.best_f.is_nan()));
+    assert!(history.last().unwrap().nan > 0);
+}
+
 #[test]
 fn de() {
     test::<DE<_>>(
         TestObj::default(),
         DESetting::default().task(Task::MinFit(1e-20)),
     );
+    for strategy in [
+        Strategy::CurrentToPBest1(0.1),
+        Strategy::RandToBest2,
+        Strategy::CurrentToRand1,
+        Strategy::Trigonometric(0.05),
+    ] {
+        test::<DE<_>>(
+            TestObj::default(),
+            DESetting::default()
+                .task(Task::MinFit(1e-20))
+                .strategy(strategy),
+        );
+    }
+    for dither in [
+        Dither::Generation(1.),
+        Dither::Individual(1.),
+        Dither::Jitter(1e-3),
+    ] {
+        test::<DE<_>>(
+            TestObj::default(),
+            DESetting::default()
+                .task(Task::MinFit(1e-20))
+                .dither(dither),
+        );
+    }
+    // The population is too small for the random vectors
+    let s = DESetting::default()
+        .task(Task::MaxGen(10))
+        .pop_num(3)
+        .strategy(Strategy::RandToBest2);
+    assert_eq!(DE::solve(TestObj::default(), s, ()).base().pop_num, 6);
 }
 
 #[test]
@@ -66,6 +469,29 @@ fn pso() {
         TestObj::default(),
         PSOSetting::default().task(Task::MinFit(1e-20)),
     );
+    for inertia in [
+        Inertia::Constant(0.5),
+        Inertia::Linear(0.9, 0.4),
+        Inertia::NonLinear(0.9, 0.4, 2.),
+    ] {
+        let setting = PSOSetting::default()
+            .task(Task::MaxGen(200))
+            .inertia(inertia)
+            .cognition(1.5)
+            .social(1.5);
+        let a = PSO::solve(TestObj::default(), setting, ());
+        assert!(a.result() < 1e-10, "{}", a.result());
+    }
+    for topology in [Topology::Ring(1), Topology::VonNeumann, Topology::Random(3)] {
+        for fully_informed in [false, true] {
+            let setting = PSOSetting::default()
+                .task(Task::MaxGen(200))
+                .topology(topology.clone())
+                .fully_informed(fully_informed);
+            let a = PSO::solve(TestObj::default(), setting, ());
+            assert!(a.result() < 1e-10, "{}", a.result());
+        }
+    }
 }
 
 #[test]
@@ -76,12 +502,101 @@ fn fa() {
     );
 }
 
+#[test]
+fn send() {
+    fn is_send<T: Send>() {}
+    is_send::<DE<TestObj>>();
+    is_send::<PSO<TestObj>>();
+    is_send::<FA<TestObj>>();
+    is_send::<RGA<TestObj>>();
+    is_send::<TLBO<TestObj>>();
+    is_send::<ADE<TestObj>>();
+    is_send::<CMAES<TestObj>>();
+    is_send::<NSGA2<Zdt1>>();
+    is_send::<MOEAD<Zdt1>>();
+}
+
+#[test]
+fn sync() {
+    fn assert_sync<T: Sync>() {}
+    assert_sync::<DE<TestObj>>();
+    assert_sync::<PSO<TestObj>>();
+    assert_sync::<FA<TestObj>>();
+    assert_sync::<RGA<TestObj>>();
+    assert_sync::<TLBO<TestObj>>();
+    assert_sync::<ADE<TestObj>>();
+    assert_sync::<CMAES<TestObj>>();
+    assert_sync::<NSGA2<Zdt1>>();
+    assert_sync::<MOEAD<Zdt1>>();
+}
+
 #[test]
 fn rga() {
     test::<RGA<_>>(
         TestObj::default(),
         RGASetting::default().task(Task::MinFit(1e-20)),
     );
+    for r in [Replacement::ReplaceWorst(2), Replacement::ReplaceParent(20)] {
+        test::<RGA<_>>(
+            TestObj::default(),
+            RGASetting::default()
+                .task(Task::MinFit(1e-20))
+                .pop_num(100)
+                .replacement(r),
+        );
+    }
+}
+
+#[test]
+fn operator() {
+    let mut rng = Rng::new(Some(0));
+    let fitness = ndarray::arr1(&[3., 1., 2., f64::NAN]);
+    let violation = ndarray::arr1(&[0., 0., 0., 0.]);
+    let pop = Population {
+        fitness: fitness.view(),
+        violation: violation.view(),
+        rank: &[2, 0, 1, 3],
+    };
+    // The best one always wins the largest tournament
+    let chosen = Tournament(100).select(&mut rng, &pop, 10);
+    assert!(chosen.iter().all(|&i| i == 1));
+    let chosen = BinaryTournament(1.).select(&mut rng, &pop, 100);
+    let count = |i| chosen.iter().filter(|&&j| j == i).count();
+    assert!(count(1) > count(0) && count(0) > count(3));
+    let selections: [Box<dyn Selection>; 3] =
+        [Box::new(Roulette), Box::new(Rank(2.)), Box::new(SUS)];
+    for selection in selections {
+        let chosen = selection.select(&mut rng, &pop, 100);
+        let count = |i| chosen.iter().filter(|&&j| j == i).count();
+        assert_eq!(chosen.len(), 100);
+        assert!(count(1) > count(0) && count(3) == 0);
+    }
+    let a = ndarray::arr1(&[0., 1., 2.]);
+    let b = ndarray::arr1(&[4., 3., 2.]);
+    let (c1, c2) = BLX(0.5).cross(&mut rng, a.view(), b.view());
+    for s in 0..3 {
+        let d = (a[s] - b[s]).abs() * 0.5;
+        for c in [c1[s], c2[s]] {
+            assert!(c >= a[s].min(b[s]) - d && c <= a[s].max(b[s]) + d);
+        }
+    }
+    let crossovers: [Box<dyn Crossover>; 3] =
+        [Box::new(SBX(2.)), Box::new(Arithmetic), Box::new(Uniform)];
+    for crossover in crossovers {
+        let (c1, c2) = crossover.cross(&mut rng, a.view(), b.view());
+        // The mean of the parents is kept
+        assert!(((&c1 + &c2) - (&a + &b)).iter().all(|d| d.abs() < 1e-12));
+    }
+    let mutations: [Box<dyn Mutation>; 3] = [
+        Box::new(Polynomial(20.)),
+        Box::new(Gaussian(0.1)),
+        Box::new(NonUniform(5.)),
+    ];
+    for mutation in mutations {
+        let v = mutation.mutate(&mut rng, 5., 0., 10., 0.5);
+        assert!(v != 5. && v.is_finite());
+    }
+    assert_eq!(NonUniform(5.).mutate(&mut rng, 5., 0., 10., 1.), 5.);
 }
 
 #[test]
@@ -91,3 +606,573 @@ fn tlbo() {
         TLBOSetting::default().task(Task::MinFit(1e-20)),
     );
 }
+
+#[test]
+fn ade() {
+    for adaptation in [Adaptation::JADE, Adaptation::SHADE] {
+        test::<ADE<_>>(
+            TestObj::default(),
+            ADESetting::default()
+                .task(Task::MinFit(1e-20))
+                .adaptation(adaptation),
+        );
+    }
+    let setting = ADESetting::default()
+        .task(Task::MaxGen(200))
+        .adaptation(Adaptation::LSHADE);
+    let a = ADE::solve(TestObj::default(), setting, ());
+    assert!(a.result() < 1e-10, "{}", a.result());
+    // The population is reduced linearly
+    assert_eq!(a.base().pop_num, 4);
+    let report = a.history().pop().unwrap();
+    assert!(matches!(report.f_mean, Some(f) if f > 0. && f <= 1.));
+    assert!(matches!(report.cr_mean, Some(cr) if (0. ..=1.).contains(&cr)));
+    let setting = ADESetting::default().task(Task::MaxGen(10)).pop_num(1);
+    assert_eq!(
+        ADE::solve(TestObj::default(), setting, ()).base().pop_num,
+        4
+    );
+}
+
+#[test]
+fn cmaes() {
+    test::<CMAES<_>>(
+        TestObj::default(),
+        CMAESSetting::default().task(Task::MinFit(1e-20)),
+    );
+    let s = |restart| {
+        CMAESSetting::default()
+            .task(Task::MaxGen(300))
+            .restart(restart)
+    };
+    let a = CMAES::solve(TestObj::default(), s(Restart::IPOP), ());
+    assert!(a.result().abs() < 1e-10, "{}", a.result());
+    // The population is doubled after restarting
+    assert!(a.base().pop_num > 8, "{}", a.base().pop_num);
+    let a = CMAES::solve(TestObj::default(), s(Restart::BIPOP), ());
+    assert!(a.result().abs() < 1e-10, "{}", a.result());
+    assert_eq!(a.history().last().unwrap().gen, 300);
+    // The population number is the alias of lambda
+    let s = || CMAESSetting::default().task(Task::MaxGen(5));
+    assert_eq!(CMAES::solve(TestObj::default(), s(), ()).base().pop_num, 8);
+    let a = CMAES::solve(TestObj::default(), s().pop_num(20), ());
+    assert_eq!(a.base().pop_num, 20);
+    let a = CMAES::solve(TestObj::default(), s().pop_num(20).lambda(12), ());
+    assert_eq!(a.base().pop_num, 12);
+}
+
+#[test]
+fn nsga2() {
+    test_pareto::<NSGA2<_>>(NSGA2Setting::default().task(Task::MaxGen(100)));
+    assert!(dominates(
+        &ndarray::arr1(&[1., 2.]),
+        &ndarray::arr1(&[1., f64::NAN])
+    ));
+}
+
+#[test]
+fn moead() {
+    test_pareto::<MOEAD<_>>(MOEADSetting::default().task(Task::MaxGen(100)));
+    test_pareto::<MOEAD<_>>(
+        MOEADSetting::default()
+            .task(Task::MaxGen(100))
+            .decomposition(Decomposition::WeightedSum),
+    );
+    let s = MOEADSetting::default()
+        .task(Task::MaxGen(10))
+        .pop_num(3)
+        .strategy(Strategy::RandToBest2);
+    assert!(MOEAD::solve(Zdt1::default(), s, ()).base().pop_num > 5);
+    // Only the subproblems are evaluated
+    let s = MOEADSetting::default().task(Task::MaxGen(0)).pop_num(150);
+    let a = MOEAD::solve(Zdt1::default(), s, ());
+    assert_eq!(a.base().report.eval, a.base().pop_num as u64);
+    // All variables of the children are recombined with the default crossing probability
+    let obj = RecordObj::default();
+    let a = MOEAD::solve(obj, MOEADSetting::default().task(Task::MaxGen(1)), ());
+    let record = a.base().func.1.lock().unwrap();
+    let (parents, children) = record.split_at(a.base().pop_num);
+    for child in children {
+        let diff = parents
+            .iter()
+            .map(|p| p.iter().zip(child).filter(|(p, c)| p != c).count())
+            .min()
+            .unwrap();
+        assert_eq!(diff, 4);
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn macros() {
+    let v: f64 = rand!();
+    assert!((0. ..1.).contains(&v));
+    assert!((2..5).contains(&rand!(2, 5)));
+    assert!(maybe!(1.));
+}
+
+#[test]
+fn seed() {
+    test_seed::<DE<_>>(|| DESetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed::<PSO<_>>(|| PSOSetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed::<FA<_>>(|| FASetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed::<RGA<_>>(|| RGASetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed::<RGA<_>>(|| {
+        RGASetting::default()
+            .task(Task::MaxGen(30))
+            .replacement(Replacement::ReplaceWorst(2))
+            .seed(Some(0))
+    });
+    test_seed::<TLBO<_>>(|| TLBOSetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed::<ADE<_>>(|| ADESetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed::<CMAES<_>>(|| CMAESSetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed_pareto::<NSGA2<_>>(|| NSGA2Setting::default().task(Task::MaxGen(30)).seed(Some(0)));
+    test_seed_pareto::<MOEAD<_>>(|| MOEADSetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+}
+
+#[test]
+fn threads() {
+    test_threads::<DE<_>>(|n| {
+        DESetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+    test_threads::<PSO<_>>(|n| {
+        PSOSetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+    test_threads::<FA<_>>(|n| {
+        FASetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+    test_threads::<RGA<_>>(|n| {
+        RGASetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+    test_threads::<TLBO<_>>(|n| {
+        TLBOSetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+    test_threads::<ADE<_>>(|n| {
+        ADESetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+    test_threads::<CMAES<_>>(|n| {
+        CMAESSetting::default()
+            .task(Task::MaxGen(30))
+            .seed(Some(0))
+            .threads(n)
+    });
+}
+
+// The parallel evaluation calls `fitness_batch` for each chunk
+#[cfg(not(feature = "parallel"))]
+#[test]
+fn batch() {
+    let a = DE::solve(
+        BatchObj::default(),
+        DESetting::default().task(Task::MaxGen(20)),
+        (),
+    );
+    assert_eq!(a.base().func.1.load(Ordering::Relaxed), 21);
+    let a = TLBO::solve(
+        BatchObj::default(),
+        TLBOSetting::default().task(Task::MaxGen(20)),
+        (),
+    );
+    assert_eq!(a.base().func.1.load(Ordering::Relaxed), 41);
+}
+
+#[test]
+fn max_eval() {
+    test_eval::<DE<_>>(DESetting::default().task(Task::MaxEval(1234)), 1234);
+    test_eval::<PSO<_>>(PSOSetting::default().task(Task::MaxEval(1234)), 1234);
+    test_eval::<FA<_>>(FASetting::default().task(Task::MaxEval(1234)), 1234);
+    test_eval::<RGA<_>>(RGASetting::default().task(Task::MaxEval(1234)), 1234);
+    test_eval::<TLBO<_>>(TLBOSetting::default().task(Task::MaxEval(1234)), 1234);
+    test_eval::<ADE<_>>(ADESetting::default().task(Task::MaxEval(1234)), 1234);
+    test_eval::<CMAES<_>>(CMAESSetting::default().task(Task::MaxEval(1234)), 1234);
+    // The budget is smaller than the population
+    test_eval::<DE<_>>(DESetting::default().task(Task::MaxEval(5)), 5);
+    let a = NSGA2::solve(
+        Zdt1::default(),
+        NSGA2Setting::default().task(Task::MaxEval(1234)),
+        (),
+    );
+    assert_eq!(a.base().report.eval, 1234);
+    let a = MOEAD::solve(
+        Zdt1::default(),
+        MOEADSetting::default().task(Task::MaxEval(1234)),
+        (),
+    );
+    assert_eq!(a.base().report.eval, 1234);
+    // No candidate is evaluated
+    let a = NSGA2::solve(
+        Zdt1::default(),
+        NSGA2Setting::default().task(Task::MaxEval(0)),
+        (),
+    );
+    assert_eq!(a.base().report.eval, 0);
+    let a = MOEAD::solve(
+        Zdt1::default(),
+        MOEADSetting::default().task(Task::MaxEval(0)),
+        (),
+    );
+    assert_eq!(a.base().report.eval, 0);
+    let a = TLBO::solve(
+        BatchObj::default(),
+        TLBOSetting::default().task(Task::MaxGen(20)),
+        (),
+    );
+    assert_eq!(
+        a.base().report.eval,
+        a.base().func.2.load(Ordering::Relaxed) as u64
+    );
+}
+
+#[test]
+fn task() {
+    let s = |task| DESetting::default().task(task);
+    let a = DE::solve(
+        TestObj::default(),
+        s(Task::AnyOf(vec![Task::MinFit(-1.), Task::MaxGen(30)])),
+        (),
+    );
+    assert_eq!(a.base().report.gen, 30);
+    assert!(matches!(a.base().stopped_by, Some(Task::MaxGen(30))));
+    let a = DE::solve(
+        TestObj::default(),
+        s(Task::AllOf(vec![Task::MaxGen(10), Task::MaxGen(20)])),
+        (),
+    );
+    assert_eq!(a.base().report.gen, 20);
+    assert!(matches!(a.base().stopped_by, Some(Task::AllOf(_))));
+    let a = DE::solve(TestObj::default(), s(Task::predicate(|r| r.gen >= 15)), ());
+    assert_eq!(a.base().report.gen, 15);
+    assert!(matches!(a.base().stopped_by, Some(Task::Predicate(_))));
+    let a = DE::solve(
+        BatchObj::default(),
+        s(Task::AnyOf(vec![Task::MaxGen(1000), Task::MaxEval(500)])),
+        (),
+    );
+    assert_eq!(a.base().report.eval, 500);
+    assert!(matches!(a.base().stopped_by, Some(Task::MaxEval(500))));
+    for task in [
+        Task::Stagnation(10, 1.),
+        Task::FitSpread(1e-6),
+        Task::Collapse(1e-6),
+    ] {
+        let a = DE::solve(
+            TestObj::default(),
+            s(Task::AnyOf(vec![Task::MaxGen(1000), task])),
+            (),
+        );
+        let b = a.base();
+        assert!(b.report.gen < 1000, "{:?}", b.stopped_by);
+        match b.stopped_by {
+            Some(Task::Stagnation(..)) => (),
+            Some(Task::FitSpread(_)) => assert!(b.fitness_spread() < 1e-6),
+            Some(Task::Collapse(_)) => assert!(b.diameter() < 1e-6),
+            _ => panic!("{:?}", b.stopped_by),
+        }
+    }
+    // Interrupted by the callback
+    let a = DE::solve(TestObj::default(), s(Task::MaxGen(100)), |r: Report| {
+        r.gen >= 50
+    });
+    assert!(a.base().stopped_by.is_none());
+}
+
+#[test]
+fn progress() {
+    fn check(p: &[f64]) {
+        assert!(p.iter().all(|p| (0.0..=1.).contains(p)), "{:?}", p);
+        assert!(p.windows(2).all(|w| w[0] <= w[1]), "{:?}", p);
+        assert_eq!(p[0], 0.);
+        assert_eq!(p[p.len() - 1], 1.);
+    }
+    let create = |task| DE::create(TestObj::default(), DESetting::default().task(task));
+    let mut a = create(Task::MaxGen(20));
+    let mut p = Vec::new();
+    for _ in 0..30 {
+        p.push(a.base().progress());
+        a.base_mut().report.next_gen();
+    }
+    check(&p);
+    let mut a = create(Task::MaxTime(2.));
+    let mut p = Vec::new();
+    for t in 0..30 {
+        a.base_mut().report.time = t as f64 * 0.1;
+        p.push(a.base().progress());
+    }
+    check(&p);
+    let a = create(Task::MaxEval(100));
+    let mut p = Vec::new();
+    for _ in 0..30 {
+        p.push(a.base().progress());
+        a.base().budget(7);
+    }
+    check(&p);
+    // No expected end
+    for task in [Task::MinFit(1e-20), Task::SlowDown(0.999)] {
+        let mut a = create(task);
+        for _ in 0..30 {
+            a.base_mut().report.next_gen();
+            a.base_mut().report.time += 1.;
+            a.base().budget(7);
+            assert_eq!(a.base().progress(), 0.);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "empty conditions of the task")]
+fn empty_task() {
+    let task = || Task::AnyOf(vec![Task::MaxGen(10), Task::AllOf(Vec::new())]);
+    let e = DE::try_solve(TestObj::default(), DESetting::default().task(task()), ());
+    assert!(matches!(e, Err(Error::EmptyTask)));
+    DE::solve(TestObj::default(), DESetting::default().task(task()), ());
+}
+
+#[test]
+fn solution() {
+    let s = |task| DESetting::default().task(task).seed(Some(1));
+    let a = DE::solve(TestObj::default(), s(Task::MaxGen(30)), ());
+    let (x, y) = a.parameters();
+    let history = a.history();
+    let eval = a.base().report.eval;
+    let s1 = a.into_solution();
+    assert_eq!(s1.best, x);
+    assert_eq!(s1.best_f, y);
+    assert_eq!(s1.result, y);
+    assert_eq!(s1.history.len(), history.len());
+    assert_eq!(s1.eval, eval);
+    assert!(s1.time > 0.);
+    assert_eq!(s1.seed, 1);
+    assert!(matches!(
+        s1.termination,
+        Termination::Task(Task::MaxGen(30))
+    ));
+    let a = DE::solve(TestObj::default(), s(Task::MaxGen(100)), |r: Report| {
+        r.gen >= 50
+    });
+    assert!(matches!(
+        a.into_solution().termination,
+        Termination::Callback
+    ));
+    // Solve into the solution directly, which is reproducible by the seed
+    let s2 = DE::solution(TestObj::default(), s(Task::MaxGen(30)), ());
+    assert_eq!(s2.best, s1.best);
+    assert_eq!(s2.clone().best_f, s1.best_f);
+    assert!(format!("{:?}", s2).starts_with("Solution {"));
+    let e = DE::try_solution(TestObj::default(), s(Task::AnyOf(Vec::new())), ());
+    assert!(matches!(e, Err(Error::EmptyTask)));
+}
+
+#[test]
+fn stats() {
+    let s = || DESetting::default().task(Task::MaxGen(100)).rpt(10);
+    let a = DE::solve(TestObj::default(), s(), ());
+    assert!(a.history().iter().all(|r| r.stats.is_none()));
+    let a = DE::solve(TestObj::default(), s().stats(true), ());
+    let history = a.history();
+    assert_eq!(history.len(), 11);
+    for r in &history {
+        let s = r.stats.as_ref().unwrap();
+        assert!(r.best_f <= s.median && s.median <= s.worst, "{:?}", s);
+        assert!(r.best_f <= s.mean && s.mean <= s.worst, "{:?}", s);
+        assert!(s.std >= 0. && s.diversity >= 0., "{:?}", s);
+        assert_eq!(s.best.len(), 4);
+    }
+    let first = history[0].stats.as_ref().unwrap().diversity;
+    let last = history[10].stats.as_ref().unwrap().diversity;
+    assert!(last < first, "{} {}", first, last);
+    // The statistics are not kept between the reporting intervals
+    let stale = Task::predicate(|r| r.gen % 10 != 0 && r.stats.is_some());
+    let task = Task::AnyOf(vec![Task::MaxGen(100), stale]);
+    let a = DE::solve(TestObj::default(), s().stats(true).task(task), ());
+    assert!(matches!(a.base().stopped_by, Some(Task::MaxGen(100))));
+}
+
+#[test]
+fn failure() {
+    let s = || DESetting::default().task(Task::MaxGen(20)).pop_num(50);
+    let a = DE::solve(FailObj::default(), s(), ());
+    let (x, y) = a.parameters();
+    assert!(x[0] <= 25. && y.is_finite(), "{} {}", x[0], y);
+    // The first population is failed
+    let f = || FailObj {
+        fail_first: 50,
+        ..Default::default()
+    };
+    let a = DE::solve(f(), s(), ());
+    assert_eq!(a.history()[0].best_f, f64::INFINITY);
+    let a = DE::solve(f(), s().failure(FailurePolicy::Retry(1)), ());
+    assert!(a.history()[0].best_f.is_finite());
+    let e = DE::try_solve(FailObj::default(), s().failure(FailurePolicy::Abort), ());
+    assert!(matches!(e, Err(Error::Objective(_))));
+    let obj = FailObj {
+        panic: true,
+        ..Default::default()
+    };
+    let e = DE::try_solve(obj, s().failure(FailurePolicy::Abort), ());
+    assert!(matches!(e, Err(Error::Panic(msg)) if msg == "simulator crashed"));
+    // The single evaluation follows the same policy
+    let obj = FailObj {
+        panic: true,
+        ..Default::default()
+    };
+    let mut a = DE::create(obj, s());
+    a.base_mut().pool[[0, 0]] = 50.;
+    a.base_mut().fitness(0);
+    assert_eq!(a.base().fitness[0], f64::INFINITY);
+    // The panicked candidates don't fail the others in the batch,
+    // and only they are retried
+    let obj = FailObj {
+        panic: true,
+        ..Default::default()
+    };
+    let a = DE::create(obj, s().failure(FailurePolicy::Retry(2)));
+    let v = ndarray::array![[0., 1., 0., 0.], [30., 0., 0., 0.], [1., 0., 0., 0.]];
+    let (f, _) = a.base().evaluate(v.view());
+    assert_eq!(f.to_vec(), vec![8., f64::INFINITY, 1.]);
+    assert_eq!(a.base().func.calls.load(Ordering::Relaxed), 5);
+}
+
+#[test]
+fn nan() {
+    test_nan::<DE<_>>(DESetting::default().task(Task::MaxGen(30)));
+    test_nan::<PSO<_>>(PSOSetting::default().task(Task::MaxGen(30)));
+    test_nan::<FA<_>>(FASetting::default().task(Task::MaxGen(30)));
+    test_nan::<RGA<_>>(RGASetting::default().task(Task::MaxGen(30)));
+    test_nan::<TLBO<_>>(TLBOSetting::default().task(Task::MaxGen(30)));
+    test_nan::<ADE<_>>(ADESetting::default().task(Task::MaxGen(30)));
+    // The small population may never sample the NaN region with a random seed
+    test_nan::<CMAES<_>>(CMAESSetting::default().task(Task::MaxGen(30)).seed(Some(0)));
+}
+
+fn test_constraint<S>(setting: S::Setting, tol: f64)
+where
+    S: Solver<ConsObj>,
+{
+    let a = S::solve(ConsObj::default(), setting, ());
+    let history = a.history();
+    // The violation of the best is never increased
+    assert!(history.windows(2).all(|w| w[1].violation <= w[0].violation));
+    let report = history.last().unwrap();
+    assert!(report.violation < tol, "{}", report.violation);
+    if report.violation == 0. {
+        assert_eq!(report.feasible_f, report.best_f);
+    }
+}
+
+#[test]
+fn constraint() {
+    let base = AlgorithmBase::new(ConsObj::default(), Setting::default());
+    let v = base.violation_of(&ndarray::arr1(&[8., 0., 6., 0.]));
+    assert!((v - (2. + 1. - 1e-4)).abs() < 1e-12, "{}", v);
+    assert!(base.is_better_than((10., 0.), (1., 1e-3)));
+    assert!(base.is_better_than((10., 1e-3), (1., 1e-2)));
+    let setting = Setting::default().constraint(ConstraintHandling::Epsilon(1e-2));
+    let base = AlgorithmBase::new(ConsObj::default(), setting);
+    assert!(base.is_better_than((1., 1e-2), (10., 0.)));
+    test_constraint::<DE<_>>(DESetting::default().task(Task::MaxGen(100)), 1e-2);
+    test_constraint::<PSO<_>>(PSOSetting::default().task(Task::MaxGen(100)), 1e-2);
+    test_constraint::<FA<_>>(FASetting::default().task(Task::MaxGen(100)), 1e-2);
+    test_constraint::<RGA<_>>(RGASetting::default().task(Task::MaxGen(100)), 1e-2);
+    test_constraint::<TLBO<_>>(TLBOSetting::default().task(Task::MaxGen(100)), 1.);
+    test_constraint::<ADE<_>>(ADESetting::default().task(Task::MaxGen(100)), 1e-2);
+    test_constraint::<CMAES<_>>(CMAESSetting::default().task(Task::MaxGen(100)), 1e-2);
+}
+
+fn test_mixed<S>(setting: fn(BoundaryHandling) -> S::Setting)
+where
+    S: Solver<MixedObj>,
+{
+    use BoundaryHandling::*;
+    for b in [Clamp, Reflect, Wrap, Random, Midpoint, Discard] {
+        let a = S::solve(MixedObj::default(), setting(b.clone()), ());
+        for v in a.base().pool.outer_iter() {
+            assert_eq!(v[0], v[0].round(), "{:?}", b);
+            assert!([3., 7., 11., 20.].contains(&v[1]), "{:?} {}", b, v[1]);
+        }
+        let values = a.values();
+        assert!(matches!(values[0], Value::Int(_)));
+        assert!(matches!(values[1], Value::Discrete(i) if i < 4));
+        assert!(matches!(values[2], Value::Float(_)));
+    }
+}
+
+#[test]
+fn mixed() {
+    test_mixed::<DE<_>>(|b| DESetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_mixed::<PSO<_>>(|b| PSOSetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_mixed::<FA<_>>(|b| FASetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_mixed::<RGA<_>>(|b| RGASetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_mixed::<RGA<_>>(|b| {
+        RGASetting::default()
+            .task(Task::MaxGen(10))
+            .boundary(b)
+            .selection(Box::new(SUS))
+            .crossover(Box::new(BLX(0.5)))
+            .mutation(Box::new(Gaussian(0.1)))
+    });
+    test_mixed::<TLBO<_>>(|b| TLBOSetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_mixed::<ADE<_>>(|b| ADESetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_mixed::<CMAES<_>>(|b| CMAESSetting::default().task(Task::MaxGen(10)).boundary(b));
+    let a = DE::solve(
+        MixedObj::default(),
+        DESetting::default().task(Task::MaxGen(50)),
+        (),
+    );
+    assert_eq!(a.values()[..2], [Value::Int(2), Value::Discrete(1)]);
+}
+
+fn test_boundary<S>(setting: fn(BoundaryHandling) -> S::Setting)
+where
+    S: Solver<TestObj>,
+{
+    use BoundaryHandling::*;
+    for b in [Clamp, Reflect, Wrap, Random, Midpoint, Discard] {
+        let a = S::solve(TestObj::default(), setting(b.clone()), ());
+        let pool = &a.base().pool;
+        assert!(pool.iter().all(|&v| (0. ..=50.).contains(&v)), "{:?}", b);
+    }
+}
+
+#[test]
+fn boundary() {
+    use BoundaryHandling::*;
+    let mut base = AlgorithmBase::new(TestObj::default(), Setting::default());
+    for (b, v, ans) in [
+        (Clamp, 55., 50.),
+        (Reflect, 55., 45.),
+        (Reflect, -105., 5.),
+        (Wrap, 55., 5.),
+        (Wrap, -5., 45.),
+        (Midpoint, 55., 45.),
+        (Midpoint, -5., 20.),
+        (Discard, 55., 55.),
+    ] {
+        base.boundary = b;
+        assert_eq!(base.check(0, v, 40.), ans);
+    }
+    test_boundary::<DE<_>>(|b| DESetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_boundary::<PSO<_>>(|b| PSOSetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_boundary::<FA<_>>(|b| FASetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_boundary::<RGA<_>>(|b| RGASetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_boundary::<TLBO<_>>(|b| TLBOSetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_boundary::<ADE<_>>(|b| ADESetting::default().task(Task::MaxGen(10)).boundary(b));
+    test_boundary::<CMAES<_>>(|b| CMAESSetting::default().task(Task::MaxGen(10)).boundary(b));
+}
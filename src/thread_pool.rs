@@ -1,18 +1,29 @@
 //! If the `parallel` feature is enabled,
 //! this module provides a thread pool to spawn the objective function and collect the results.
 
-use crate::{ObjFunc, Report};
-use ndarray::AsArray;
+use crate::{Error, ObjFunc, Report};
+use ndarray::{s, ArrayView2};
 use std::{
-    collections::{hash_map::IntoIter, HashMap},
-    sync::Arc,
-    thread::{spawn, JoinHandle},
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{
+        mpsc::{channel, sync_channel, SyncSender},
+        Arc, Mutex,
+    },
+    thread::{self, available_parallelism, JoinHandle},
 };
 
-/// A join handler collector.
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A persistent worker pool for the objective function.
+///
+/// The workers are spawned once and reused until the pool is dropped.
+/// The candidates are split into chunks and sent to a bounded queue,
+/// so the caller will be blocked if the workers are busy.
+/// Each chunk is evaluated by [`ObjFunc::try_fitness_batch`],
+/// or by any function with [`ThreadPool::map`].
 ///
-/// This type implements [`IntoIterator`] that consume the pool,
-/// and the tasks can be wait by a for-loop.
+/// The results are collected in the original order,
+/// so the parallel evaluation has the same behavior as the serial evaluation.
 ///
 /// ```
 /// use std::sync::Arc;
@@ -37,50 +48,122 @@ use std::{
 /// #     {
 /// #         self.fitness(v, &Default::default())
 /// #     }
-/// #     fn ub(&self) -> ArrayView1<f64> { self.1.view() }
-/// #     fn lb(&self) -> ArrayView1<f64> { self.0.view() }
+/// #     fn ub(&self) -> ArrayView1<'_, f64> { self.1.view() }
+/// #     fn lb(&self) -> ArrayView1<'_, f64> { self.0.view() }
 /// # }
 ///
-/// let mut tasks = ThreadPool::new();
-/// tasks.insert(0, Arc::new(MyFunc::new()), Report::default(), &array![0., 0., 0.]);
+/// let tasks = ThreadPool::new(2);
+/// let v = array![[0., 0., 0.], [1., 0., 0.], [1., 1., 0.]];
 ///
-/// for (i, f) in tasks {
-///     assert_eq!(i, 0);
-///     assert_eq!(f, 0.);
-/// }
+/// let ans = tasks.evaluate(&Arc::new(MyFunc::new()), v.view(), &Report::default());
+/// let ans = ans.into_iter().map(Result::unwrap).collect::<Vec<_>>();
+/// assert_eq!(ans, vec![0., 1., 2.]);
+/// let ans = tasks.map(v.view(), |v| v.outer_iter().map(|v| v.sum()).collect());
+/// assert_eq!(ans, vec![0., 1., 2.]);
 /// ```
-#[derive(Default)]
 pub struct ThreadPool {
-    tasks: HashMap<usize, JoinHandle<f64>>,
+    sender: Option<SyncSender<Job>>,
+    workers: Vec<JoinHandle<()>>,
 }
 
 impl ThreadPool {
-    /// Create a new thread pool.
-    pub fn new() -> Self {
-        Self::default()
+    /// Create a new thread pool with `threads` workers.
+    ///
+    /// Use zero to choose the available parallelism of the platform.
+    pub fn new(threads: usize) -> Self {
+        let threads = if threads == 0 {
+            available_parallelism().map_or(1, |n| n.get())
+        } else {
+            threads
+        };
+        let (sender, jobs) = sync_channel::<Job>(threads * 2);
+        let jobs = Arc::new(Mutex::new(jobs));
+        let workers = (0..threads)
+            .map(|_| {
+                let jobs = jobs.clone();
+                thread::spawn(move || loop {
+                    let job = jobs.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            workers,
+        }
+    }
+
+    /// Number of workers.
+    pub fn threads(&self) -> usize {
+        self.workers.len()
     }
 
-    /// Spawn a objective function task.
-    pub fn insert<'a, F, V>(&mut self, i: usize, f: Arc<F>, report: Report, v: V)
+    /// Evaluate the candidates, each row is a candidate.
+    ///
+    /// If the objective function panicked, the panicked candidates are [`Error::Panic`].
+    /// The whole chunk is failed only if the user implementation of
+    /// [`ObjFunc::try_fitness_batch`] or [`ObjFunc::fitness_batch`] panicked.
+    pub fn evaluate<F: ObjFunc>(
+        &self,
+        func: &Arc<F>,
+        v: ArrayView2<f64>,
+        report: &Report,
+    ) -> Vec<Result<f64, Error>> {
+        let func = func.clone();
+        let report = report.clone();
+        self.map(v, move |v| {
+            crate::error::try_fitness_batch(&*func, v, &report)
+        })
+    }
+
+    /// Apply function `f` on the chunks of the candidates, each row is a candidate.
+    ///
+    /// The function should return a result for each row.
+    /// If the function panicked, the panic will be resumed by the caller.
+    pub fn map<T, G>(&self, v: ArrayView2<f64>, f: G) -> Vec<T>
     where
-        F: ObjFunc,
-        V: AsArray<'a, f64>,
+        T: Send + 'static,
+        G: Fn(ArrayView2<f64>) -> Vec<T> + Send + Sync + 'static,
     {
-        let v = Arc::new(v.into().to_owned());
-        self.tasks.insert(i, spawn(move || f.fitness(&*v, &report)));
+        if v.nrows() == 0 {
+            return Vec::new();
+        }
+        let f = Arc::new(f);
+        let size = v.nrows().div_ceil(self.threads());
+        let (output, receiver) = channel();
+        let mut n = 0;
+        let sender = self.sender.as_ref().unwrap();
+        for i in (0..v.nrows()).step_by(size) {
+            let chunk = v.slice(s![i..(i + size).min(v.nrows()), ..]).to_owned();
+            let f = f.clone();
+            let output = output.clone();
+            let job = move || {
+                let ans = catch_unwind(AssertUnwindSafe(|| f(chunk.view())));
+                let _ = output.send((n, ans));
+            };
+            sender.send(Box::new(job)).unwrap();
+            n += 1;
+        }
+        let mut ans = (0..n).map(|_| Vec::new()).collect::<Vec<_>>();
+        for _ in 0..n {
+            let (i, f) = receiver.recv().unwrap();
+            match f {
+                Ok(f) => ans[i] = f,
+                Err(e) => resume_unwind(e),
+            }
+        }
+        ans.into_iter().flatten().collect()
     }
 }
 
-impl IntoIterator for ThreadPool {
-    type Item = (usize, f64);
-    type IntoIter = IntoIter<usize, f64>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        let m = self
-            .tasks
-            .into_iter()
-            .map(|(i, j)| (i, j.join().unwrap()))
-            .collect::<HashMap<_, _>>();
-        m.into_iter()
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for w in self.workers.drain(..) {
+            let _ = w.join();
+        }
     }
 }
@@ -1,6 +1,13 @@
 use crate::*;
-use ndarray::{s, Array1, Array2, AsArray};
-use std::{sync::Arc, time::Instant};
+use ndarray::{s, Array1, Array2, ArrayView2, AsArray, Axis};
+use std::{
+    cmp::Ordering,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering::Relaxed},
+        Arc, OnceLock,
+    },
+    time::Instant,
+};
 
 /// The data of generation sampling.
 #[derive(Clone, Debug)]
@@ -11,6 +18,42 @@ pub struct Report {
     pub best_f: f64,
     /// Time duration.
     pub time: f64,
+    /// Number of the NaN fitness values returned by the objective function.
+    pub nan: usize,
+    /// Number of the objective function evaluations.
+    pub eval: u64,
+    /// The total constraint violation of the best.
+    pub violation: f64,
+    /// The best fitness of the feasible individuals.
+    pub feasible_f: f64,
+    /// The mean of the adapted F factors, only for the adaptive methods.
+    pub f_mean: Option<f64>,
+    /// The mean of the adapted crossing probabilities, only for the adaptive methods.
+    pub cr_mean: Option<f64>,
+    /// The population statistics, computed at each reporting interval
+    /// if the `stats` option of the settings is enabled.
+    /// It is `None` between the reporting intervals.
+    pub stats: Option<Stats>,
+}
+
+/// The population statistics of a report.
+///
+/// The mean, median and standard deviation only count the finite fitness values,
+/// which are NaN if there is no finite fitness.
+#[derive(Clone, Debug)]
+pub struct Stats {
+    /// Mean of the fitness.
+    pub mean: f64,
+    /// Median of the fitness.
+    pub median: f64,
+    /// The worst fitness, see [`is_better`].
+    pub worst: f64,
+    /// Standard deviation of the fitness.
+    pub std: f64,
+    /// Population diversity, the mean distance of the individuals to their centroid.
+    pub diversity: f64,
+    /// The current best variables.
+    pub best: Array1<f64>,
 }
 
 impl Default for Report {
@@ -19,6 +62,13 @@ impl Default for Report {
             gen: 0,
             best_f: f64::INFINITY,
             time: 0.,
+            nan: 0,
+            eval: 0,
+            violation: 0.,
+            feasible_f: f64::INFINITY,
+            f_mean: None,
+            cr_mean: None,
+            stats: None,
         }
     }
 }
@@ -35,7 +85,55 @@ impl Report {
     }
 }
 
+/// Return true if the fitness `a` is better than `b`.
+///
+/// This is the comparison policy of all algorithms,
+/// the smaller value is better, and NaN is treated as the worst value.
+/// ```
+/// use metaheuristics_nature::is_better;
+///
+/// assert!(is_better(1., 2.));
+/// assert!(is_better(f64::INFINITY, f64::NAN));
+/// assert!(!is_better(f64::NAN, f64::INFINITY));
+/// assert!(!is_better(f64::NAN, f64::NAN));
+/// ```
+#[inline(always)]
+pub fn is_better(a: f64, b: f64) -> bool {
+    a < b || (b.is_nan() && !a.is_nan())
+}
+
+/// The constraint handling strategy,
+/// decides how to rank the candidates with their constraint violations.
+///
+/// See [`ObjFunc::inequality`] and [`ObjFunc::equality`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstraintHandling {
+    /// Deb's feasibility rules.
+    ///
+    /// + The feasible candidate is better than the infeasible candidate.
+    /// + Compare the fitness if both are feasible.
+    /// + Compare the violation if both are infeasible.
+    Feasibility,
+    /// Epsilon-constrained comparison.
+    ///
+    /// The candidates with the violation under epsilon are treated as feasible,
+    /// otherwise as the feasibility rules.
+    Epsilon(f64),
+}
+
 /// The terminal condition of the algorithm setting.
+///
+/// The conditions can be combined by [`Task::AnyOf`] and [`Task::AllOf`],
+/// and the fired condition is recorded in [`AlgorithmBase::stopped_by`].
+/// ```
+/// use metaheuristics_nature::Task;
+///
+/// // Stop at 1e-8 fitness or after 60 seconds, whichever first
+/// let task = Task::AnyOf(vec![Task::MinFit(1e-8), Task::MaxTime(60.)]);
+/// // Stop by a user-defined condition
+/// let task = Task::AllOf(vec![Task::MaxGen(100), Task::predicate(|r| r.best_f < 1.)]);
+/// ```
+#[derive(Clone)]
 pub enum Task {
     /// Max generation.
     MaxGen(u32),
@@ -44,7 +142,211 @@ pub enum Task {
     /// Max time in second.
     MaxTime(f32),
     /// Minimum delta value.
+    ///
+    /// The ratio of two consecutive improvements is unstable,
+    /// please consider [`Task::Stagnation`] instead.
     SlowDown(f64),
+    /// Max number of the objective function evaluations.
+    ///
+    /// The candidates over the budget are not evaluated,
+    /// and they are treated as the worst (positive infinity) like the failed evaluations.
+    /// The budget also works in [`Task::AnyOf`].
+    MaxEval(u64),
+    /// The best fitness is not improved more than the tolerance for N generations.
+    ///
+    /// The improvement is measured from the last significant improvement,
+    /// and the reduction of the constraint violation is also an improvement.
+    Stagnation(u32, f64),
+    /// The fitness spread of the population (the worst minus the best) is less than the value,
+    /// see [`AlgorithmBase::fitness_spread`].
+    FitSpread(f64),
+    /// The population diameter is less than the value,
+    /// see [`AlgorithmBase::diameter`].
+    Collapse(f64),
+    /// Any of the conditions is met.
+    ///
+    /// The conditions should not be empty, otherwise the run returns [`Error::EmptyTask`].
+    AnyOf(Vec<Task>),
+    /// All of the conditions are met.
+    ///
+    /// The conditions should not be empty, otherwise the run returns [`Error::EmptyTask`].
+    AllOf(Vec<Task>),
+    /// User-defined condition over the current report, see [`Task::predicate`].
+    Predicate(Arc<dyn Fn(&Report) -> bool + Send + Sync>),
+}
+
+impl std::fmt::Debug for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MaxGen(v) => f.debug_tuple("MaxGen").field(v).finish(),
+            Self::MinFit(v) => f.debug_tuple("MinFit").field(v).finish(),
+            Self::MaxTime(v) => f.debug_tuple("MaxTime").field(v).finish(),
+            Self::SlowDown(v) => f.debug_tuple("SlowDown").field(v).finish(),
+            Self::MaxEval(v) => f.debug_tuple("MaxEval").field(v).finish(),
+            Self::Stagnation(n, tol) => f.debug_tuple("Stagnation").field(n).field(tol).finish(),
+            Self::FitSpread(v) => f.debug_tuple("FitSpread").field(v).finish(),
+            Self::Collapse(v) => f.debug_tuple("Collapse").field(v).finish(),
+            Self::AnyOf(v) => f.debug_tuple("AnyOf").field(v).finish(),
+            Self::AllOf(v) => f.debug_tuple("AllOf").field(v).finish(),
+            Self::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+impl Task {
+    /// Create a user-defined condition over the current report.
+    pub fn predicate<P>(p: P) -> Self
+    where
+        P: Fn(&Report) -> bool + Send + Sync + 'static,
+    {
+        Self::Predicate(Arc::new(p))
+    }
+
+    /// The budget of the evaluations, the smallest one in [`Task::AnyOf`].
+    fn max_eval(&self) -> Option<u64> {
+        match self {
+            Self::MaxEval(v) => Some(*v),
+            Self::AnyOf(tasks) => tasks.iter().filter_map(Self::max_eval).min(),
+            _ => None,
+        }
+    }
+
+    /// Return true if any combinator is empty, which never fires or always fires.
+    fn has_empty(&self) -> bool {
+        match self {
+            Self::AnyOf(tasks) | Self::AllOf(tasks) => {
+                tasks.is_empty() || tasks.iter().any(Self::has_empty)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The state of a termination condition.
+struct Slot {
+    // The last improvement of `Task::SlowDown`
+    last_diff: f64,
+    // The reference best and the stalled generations of `Task::Stagnation`
+    best: (f64, f64),
+    stall: u32,
+}
+
+/// The state of the termination conditions.
+struct TaskState {
+    time_start: Instant,
+    best_f: f64,
+    diff: f64,
+    // The states of the conditions in order
+    slots: Vec<Slot>,
+}
+
+impl TaskState {
+    fn new(time_start: Instant, best_f: f64) -> Self {
+        Self {
+            time_start,
+            best_f,
+            diff: 0.,
+            slots: Vec::new(),
+        }
+    }
+
+    /// Update the state by the base, return the fired condition.
+    fn check<F: ObjFunc>(&mut self, base: &AlgorithmBase<F>) -> Option<Task> {
+        self.diff = self.best_f - base.report.best_f;
+        self.best_f = base.report.best_f;
+        let time = (Instant::now() - self.time_start).as_secs_f32();
+        self.fire(&base.task, base, time, &mut 0)
+    }
+
+    fn slot(&mut self, k: &mut usize) -> &mut Slot {
+        if *k == self.slots.len() {
+            self.slots.push(Slot {
+                last_diff: 0.,
+                best: (f64::INFINITY, f64::INFINITY),
+                stall: 0,
+            });
+        }
+        *k += 1;
+        &mut self.slots[*k - 1]
+    }
+
+    fn fire<F: ObjFunc>(
+        &mut self,
+        task: &Task,
+        base: &AlgorithmBase<F>,
+        time: f32,
+        k: &mut usize,
+    ) -> Option<Task> {
+        let report = &base.report;
+        let done = match task {
+            Task::MaxGen(v) => report.gen >= *v,
+            Task::MinFit(v) => report.violation <= 0. && report.best_f <= *v,
+            Task::MaxTime(v) => time >= *v,
+            Task::MaxEval(v) => report.eval >= *v,
+            Task::SlowDown(v) => {
+                let diff = self.diff;
+                let last_diff = std::mem::replace(&mut self.slot(k).last_diff, diff);
+                last_diff > 0. && diff / last_diff >= *v
+            }
+            Task::Stagnation(n, tol) => {
+                let slot = self.slot(k);
+                let (f, v) = (report.best_f, report.violation);
+                if slot.best.1 - v > *tol || v <= 0. && slot.best.0 - f > *tol {
+                    slot.best = (f, v);
+                    slot.stall = 0;
+                } else {
+                    slot.stall += 1;
+                }
+                slot.stall >= *n
+            }
+            Task::FitSpread(v) => base.fitness_spread() < *v,
+            Task::Collapse(v) => base.diameter() < *v,
+            Task::Predicate(p) => p(report),
+            // All conditions are checked to update their states
+            Task::AnyOf(tasks) => {
+                return tasks
+                    .iter()
+                    .map(|t| self.fire(t, base, time, k))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .flatten()
+                    .next();
+            }
+            Task::AllOf(tasks) => {
+                let n = tasks
+                    .iter()
+                    .map(|t| self.fire(t, base, time, k))
+                    .filter(Option::is_some)
+                    .count();
+                n == tasks.len()
+            }
+        };
+        if done {
+            Some(task.clone())
+        } else {
+            None
+        }
+    }
+}
+
+/// The boundary handling strategy of the variables.
+///
+/// The strategy is applied to the variable which is out of its bounds,
+/// the *parent* is the value before moving.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoundaryHandling {
+    /// Set to the nearest bound.
+    Clamp,
+    /// Reflect back from the bound.
+    Reflect,
+    /// Wrap around to the opposite bound, periodic boundary.
+    Wrap,
+    /// Reinitialize randomly between the bounds.
+    Random,
+    /// Set to the midpoint between the parent and the violated bound.
+    Midpoint,
+    /// Discard the whole candidate and keep the parent.
+    Discard,
 }
 
 setting_builder! {
@@ -56,6 +358,29 @@ setting_builder! {
         pop_num: usize = 200,
         /// The report frequency. (per generation)
         rpt: u32 = 50,
+        /// Random seed, use `None` to choose a random seed.
+        seed: Option<u64> = None,
+        /// Number of the worker threads, use zero to choose the available parallelism.
+        /// (`parallel` feature only)
+        threads: usize = 0,
+        /// The policy of the failed evaluations.
+        failure: FailurePolicy = FailurePolicy::Worst,
+        /// The boundary handling strategy.
+        boundary: BoundaryHandling = BoundaryHandling::Clamp,
+        /// The constraint handling strategy.
+        constraint: ConstraintHandling = ConstraintHandling::Feasibility,
+        /// The tolerance of the equality constraints.
+        eq_tol: f64 = 1e-4,
+        /// Record the population statistics in the reports, see [`Report::stats`].
+        stats: bool = false,
+    }
+}
+
+impl Setting {
+    /// Raise the population number to at least `n`, which is required by the algorithm.
+    pub(crate) fn pop_at_least(mut self, n: usize) -> Self {
+        self.pop_num = self.pop_num.max(n);
+        self
     }
 }
 
@@ -69,17 +394,38 @@ pub struct AlgorithmBase<F: ObjFunc> {
     rpt: u32,
     /// Termination condition.
     pub task: Task,
+    /// The fired termination condition,
+    /// which is `None` if the run is not finished or interrupted by the callback.
+    ///
+    /// For [`Task::AnyOf`], it is the first fired condition.
+    pub stopped_by: Option<Task>,
     /// The best variables.
     pub best: Array1<f64>,
     /// Current fitness of all individuals.
     pub fitness: Array1<f64>,
+    /// Current constraint violation of all individuals.
+    pub violation: Array1<f64>,
     /// Current variables of all individuals.
     pub pool: Array2<f64>,
+    /// The boundary handling strategy.
+    pub boundary: BoundaryHandling,
     /// The current information of the algorithm.
     pub report: Report,
     reports: Vec<Report>,
+    failure: FailurePolicy,
+    constraint: ConstraintHandling,
+    eq_tol: f64,
+    stats: bool,
+    error: OnceLock<Error>,
+    nan: AtomicUsize,
+    eval: AtomicU64,
+    /// The random number generator.
+    pub rng: Rng,
     /// The objective function.
     pub func: Arc<F>,
+    /// The worker pool of the objective function.
+    #[cfg(feature = "parallel")]
+    pub thread_pool: crate::thread_pool::ThreadPool,
 }
 
 impl<F: ObjFunc> AlgorithmBase<F> {
@@ -90,17 +436,35 @@ impl<F: ObjFunc> AlgorithmBase<F> {
             assert_eq!(lb.len(), ub.len(), "different dimension of the variables!");
             lb.len()
         };
+        let error = OnceLock::new();
+        if settings.task.has_empty() {
+            let _ = error.set(Error::EmptyTask);
+        }
+        let func = Arc::new(func);
         Self {
             pop_num: settings.pop_num,
             dim,
             rpt: settings.rpt,
             task: settings.task,
+            stopped_by: None,
             best: Array1::zeros(dim),
             fitness: Array1::zeros(settings.pop_num),
+            violation: Array1::zeros(settings.pop_num),
             pool: Array2::zeros((settings.pop_num, dim)),
+            boundary: settings.boundary,
             report: Default::default(),
             reports: vec![],
-            func: Arc::new(func),
+            failure: settings.failure,
+            constraint: settings.constraint,
+            eq_tol: settings.eq_tol,
+            stats: settings.stats,
+            error,
+            nan: AtomicUsize::new(0),
+            eval: AtomicU64::new(0),
+            rng: Rng::new(settings.seed),
+            #[cfg(feature = "parallel")]
+            thread_pool: crate::thread_pool::ThreadPool::new(settings.threads),
+            func,
         }
     }
 
@@ -114,17 +478,371 @@ impl<F: ObjFunc> AlgorithmBase<F> {
         self.func.ub()[i]
     }
 
+    /// Check the bounds of the index `s` with the value `v`,
+    /// `parent` is the value before moving.
+    ///
+    /// The value is repaired by the [`BoundaryHandling`] strategy,
+    /// or returned as is for [`BoundaryHandling::Discard`].
+    /// Please use [`AlgorithmBase::is_discarded`] to check the whole candidate.
+    pub fn check(&mut self, s: usize, v: f64, parent: f64) -> f64 {
+        let v = self.repair(s, v, parent);
+        self.func.variable(s).snap(v, self.lb(s), self.ub(s))
+    }
+
+    fn repair(&mut self, s: usize, v: f64, parent: f64) -> f64 {
+        let lb = self.lb(s);
+        let ub = self.ub(s);
+        if v >= lb && v <= ub {
+            return v;
+        }
+        let w = ub - lb;
+        match self.boundary {
+            BoundaryHandling::Clamp => v.max(lb).min(ub),
+            BoundaryHandling::Reflect if w > 0. => {
+                let d = (v - lb).rem_euclid(2. * w);
+                if d > w {
+                    ub - (d - w)
+                } else {
+                    lb + d
+                }
+            }
+            BoundaryHandling::Wrap if w > 0. => lb + (v - lb).rem_euclid(w),
+            BoundaryHandling::Reflect | BoundaryHandling::Wrap => lb,
+            BoundaryHandling::Random => self.random(s),
+            BoundaryHandling::Midpoint if v < lb => (lb + parent.max(lb)) * 0.5,
+            BoundaryHandling::Midpoint => (ub + parent.min(ub)) * 0.5,
+            BoundaryHandling::Discard => v,
+        }
+    }
+
+    /// Generate a random valid value of the variable at index `s`.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the allowed values of [`Variable::Discrete`] are empty.
+    pub fn random(&mut self, s: usize) -> f64 {
+        let lb = self.lb(s);
+        let ub = self.ub(s);
+        match self.func.variable(s) {
+            Variable::Discrete(values) => values[self.rng.int(0, values.len())],
+            Variable::Integer if ub.floor() > lb.ceil() => {
+                self.rng.float(lb.ceil(), ub.floor() + 1.).floor()
+            }
+            Variable::Integer => lb.ceil(),
+            Variable::Continuous if ub > lb => self.rng.float(lb, ub),
+            Variable::Continuous => lb,
+        }
+    }
+
+    /// Return true if the candidate should be discarded,
+    /// that is, it is out of bounds under [`BoundaryHandling::Discard`].
+    pub fn is_discarded<'a, A>(&self, v: A) -> bool
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        self.boundary == BoundaryHandling::Discard
+            && (0..self.dim).any(|s| v[s] < self.lb(s) || v[s] > self.ub(s))
+    }
+
     /// Get fitness from individual `i`.
+    ///
+    /// This is a single candidate of [`AlgorithmBase::evaluate`].
     pub fn fitness(&mut self, i: usize) {
-        self.fitness[i] = self.func.fitness(self.pool.slice(s![i, ..]), &self.report);
+        let (f, v) = self.evaluate(self.pool.slice(s![i..i + 1, ..]));
+        self.fitness[i] = f[0];
+        self.violation[i] = v[0];
+    }
+
+    /// Get the total constraint violation of the candidate.
+    pub fn violation_of<'a, A>(&self, v: A) -> f64
+    where
+        A: AsArray<'a, f64>,
+    {
+        let v = v.into();
+        let g = self
+            .func
+            .inequality(v)
+            .iter()
+            .map(|g| g.max(0.))
+            .sum::<f64>();
+        let h = (self.func.equality(v).iter())
+            .map(|h| (h.abs() - self.eq_tol).max(0.))
+            .sum::<f64>();
+        g + h
+    }
+
+    /// Compare the constraint violation `va` with `vb`.
+    ///
+    /// Return `None` if both are feasible under the [`ConstraintHandling`] of the settings
+    /// or their violations are equal, then the objectives should be compared.
+    /// Otherwise, return true if `va` is better.
+    pub fn cmp_violation(&self, va: f64, vb: f64) -> Option<bool> {
+        let eps = match self.constraint {
+            ConstraintHandling::Feasibility => 0.,
+            ConstraintHandling::Epsilon(eps) => eps,
+        };
+        if (va <= eps && vb <= eps) || va == vb {
+            None
+        } else {
+            Some(is_better(va, vb))
+        }
+    }
+
+    /// Return true if the candidate with fitness `a` and violation `va`
+    /// is better than the candidate with fitness `b` and violation `vb`.
+    ///
+    /// The comparison follows [`is_better`] and the [`ConstraintHandling`] of the settings.
+    pub fn is_better_than(&self, (a, va): (f64, f64), (b, vb): (f64, f64)) -> bool {
+        self.cmp_violation(va, vb)
+            .unwrap_or_else(|| is_better(a, b))
+    }
+
+    /// Return true if the individual `i` is better than the individual `j`.
+    #[inline(always)]
+    pub fn is_better_at(&self, i: usize, j: usize) -> bool {
+        self.is_better_than(
+            (self.fitness[i], self.violation[i]),
+            (self.fitness[j], self.violation[j]),
+        )
+    }
+
+    /// Return true if the candidate is better than the current best.
+    #[inline(always)]
+    pub fn is_best(&self, f: f64, v: f64) -> bool {
+        self.is_better_than((f, v), (self.report.best_f, self.report.violation))
+    }
+
+    /// The progress of the task between [0, 1], which is derived from the termination condition.
+    ///
+    /// + [`Task::MaxGen`]: the ratio of the generations.
+    /// + [`Task::MaxTime`]: the ratio of the elapsed time.
+    /// + [`Task::MaxEval`]: the ratio of the evaluations.
+    /// + [`Task::MinFit`], [`Task::Stagnation`], [`Task::FitSpread`], [`Task::Collapse`],
+    ///   [`Task::SlowDown`] and [`Task::Predicate`]: always zero, since they have no expected end.
+    /// + [`Task::AnyOf`]: the maximum progress of the conditions.
+    /// + [`Task::AllOf`]: the minimum progress of the conditions.
+    pub fn progress(&self) -> f64 {
+        self.progress_of(&self.task)
+    }
+
+    fn progress_of(&self, task: &Task) -> f64 {
+        let p = match *task {
+            Task::MaxGen(v) => self.report.gen as f64 / v as f64,
+            Task::MaxTime(v) => self.report.time / v as f64,
+            Task::MaxEval(v) => self.eval.load(Relaxed) as f64 / v as f64,
+            Task::MinFit(_)
+            | Task::Stagnation(..)
+            | Task::FitSpread(_)
+            | Task::Collapse(_)
+            | Task::SlowDown(_)
+            | Task::Predicate(_) => 0.,
+            Task::AnyOf(ref tasks) => tasks.iter().map(|t| self.progress_of(t)).fold(0., f64::max),
+            Task::AllOf(ref tasks) => tasks.iter().map(|t| self.progress_of(t)).fold(1., f64::min),
+        };
+        // NaN is treated as no progress
+        if p.is_nan() {
+            0.
+        } else {
+            p.clamp(0., 1.)
+        }
+    }
+
+    /// The fitness spread of the population, the worst fitness minus the best fitness.
+    ///
+    /// Return infinity if any fitness is not finite.
+    pub fn fitness_spread(&self) -> f64 {
+        if self.fitness.iter().any(|f| !f.is_finite()) {
+            return f64::INFINITY;
+        }
+        let max = self.fitness.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let min = self.fitness.fold(f64::INFINITY, |a, &b| a.min(b));
+        max - min
+    }
+
+    /// The population diameter,
+    /// the maximum range of the variables relative to their bounds.
+    pub fn diameter(&self) -> f64 {
+        (0..self.dim)
+            .map(|s| {
+                let v = self.pool.column(s);
+                let max = v.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                let min = v.fold(f64::INFINITY, |a, &b| a.min(b));
+                (max - min) / (self.ub(s) - self.lb(s))
+            })
+            .fold(0., f64::max)
+    }
+
+    /// Return the indices of the individuals from the best to the worst.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut order = (0..self.pop_num).collect::<Vec<_>>();
+        order.sort_by(|&i, &j| {
+            if self.is_better_at(i, j) {
+                Ordering::Less
+            } else if self.is_better_at(j, i) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        order
+    }
+
+    /// Apply function `f` on the candidates, each row is a candidate.
+    ///
+    /// If the `parallel` feature is enabled, the chunks of the candidates are sent to the workers.
+    /// See `thread_pool::ThreadPool::map`.
+    pub fn map<T, G>(&self, v: ArrayView2<f64>, f: G) -> Vec<T>
+    where
+        T: Send + 'static,
+        G: Fn(ArrayView2<f64>) -> Vec<T> + Send + Sync + 'static,
+    {
+        #[cfg(feature = "parallel")]
+        let ans = self.thread_pool.map(v, f);
+        #[cfg(not(feature = "parallel"))]
+        let ans = f(v);
+        ans
+    }
+
+    /// Count `n` evaluations, and return the number of them that are within the budget of
+    /// [`Task::MaxEval`].
+    pub(crate) fn budget(&self, n: usize) -> usize {
+        let n = match self.task.max_eval() {
+            Some(v) => n.min(v.saturating_sub(self.eval.load(Relaxed)) as usize),
+            None => n,
+        };
+        self.eval.fetch_add(n as u64, Relaxed);
+        n
+    }
+
+    /// Get fitness and constraint violation of the candidates by [`ObjFunc::try_fitness_batch`],
+    /// each row is a candidate.
+    ///
+    /// If the `parallel` feature is enabled, the candidates are evaluated by the workers.
+    /// The failed evaluations are handled by the [`FailurePolicy`] of the settings,
+    /// and the retries are also counted as the evaluations.
+    pub fn evaluate(&self, v: ArrayView2<f64>) -> (Array1<f64>, Array1<f64>) {
+        let violation = v.outer_iter().map(|v| self.violation_of(v)).collect();
+        let func = self.func.clone();
+        let report = self.report.clone();
+        let fitness = self.evaluate_by(
+            v,
+            f64::INFINITY,
+            |f| f.is_nan(),
+            move |v| crate::error::try_fitness_batch(&*func, v, &report),
+        );
+        (Array1::from(fitness), violation)
+    }
+
+    /// Evaluate the candidates by the batch function `f` within the budget,
+    /// handle the failed evaluations by the [`FailurePolicy`],
+    /// and count the NaN results by `is_nan`.
+    ///
+    /// The candidates over the budget or failed are `worst`.
+    pub(crate) fn evaluate_by<T, G>(
+        &self,
+        v: ArrayView2<f64>,
+        worst: T,
+        is_nan: fn(&T) -> bool,
+        f: G,
+    ) -> Vec<T>
+    where
+        T: Clone + Send + 'static,
+        G: Fn(ArrayView2<f64>) -> Vec<Result<T, Error>> + Clone + Send + Sync + 'static,
+    {
+        let mut ans = vec![worst; v.nrows()];
+        let mut index = (0..v.nrows()).collect::<Vec<_>>();
+        index.truncate(self.budget(index.len()));
+        let mut retry = 0;
+        while !index.is_empty() {
+            let v = v.select(Axis(0), &index);
+            let mut failed = Vec::new();
+            for (i, f) in index.into_iter().zip(self.map(v.view(), f.clone())) {
+                match f {
+                    Ok(f) => {
+                        if is_nan(&f) {
+                            self.nan.fetch_add(1, Relaxed);
+                        }
+                        ans[i] = f;
+                    }
+                    Err(e) => failed.push((i, e)),
+                }
+            }
+            index = match self.failure {
+                FailurePolicy::Retry(n) if retry < n => {
+                    retry += 1;
+                    failed.into_iter().map(|(i, _)| i).collect()
+                }
+                FailurePolicy::Abort => {
+                    if let Some((_, e)) = failed.into_iter().next() {
+                        let _ = self.error.set(e);
+                    }
+                    Vec::new()
+                }
+                _ => Vec::new(),
+            };
+            index.truncate(self.budget(index.len()));
+        }
+        ans
     }
 
     /// Set the index to best.
     pub fn set_best(&mut self, i: usize) {
         self.report.best_f = self.fitness[i];
+        self.report.violation = self.violation[i];
         self.best.assign(&self.pool.slice(s![i, ..]));
     }
 
+    /// Update the best fitness of the feasible individuals.
+    fn update_feasible(&mut self) {
+        for i in 0..self.pop_num {
+            if self.violation[i] <= 0. && is_better(self.fitness[i], self.report.feasible_f) {
+                self.report.feasible_f = self.fitness[i];
+            }
+        }
+    }
+
+    /// Compute the population statistics if it is enabled.
+    fn update_stats(&mut self) {
+        if !self.stats {
+            return;
+        }
+        let mut f = self
+            .fitness
+            .iter()
+            .copied()
+            .filter(|f| f.is_finite())
+            .collect::<Vec<_>>();
+        f.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = f.len() as f64;
+        let mean = f.iter().sum::<f64>() / n;
+        let median = match f.len() {
+            0 => f64::NAN,
+            len if len % 2 == 1 => f[len / 2],
+            len => (f[len / 2 - 1] + f[len / 2]) / 2.,
+        };
+        let std = (f.iter().map(|f| (f - mean).powi(2)).sum::<f64>() / n).sqrt();
+        let worst = self.fitness.fold(
+            f64::NEG_INFINITY,
+            |a, &b| if is_better(a, b) { b } else { a },
+        );
+        let centroid = self.pool.mean_axis(Axis(0)).unwrap();
+        let diversity = self
+            .pool
+            .outer_iter()
+            .map(|v| (&v - &centroid).mapv(|d| d * d).sum().sqrt())
+            .sum::<f64>()
+            / self.pop_num as f64;
+        self.report.stats = Some(Stats {
+            mean,
+            median,
+            worst,
+            std,
+            diversity,
+            best: self.best.clone(),
+        });
+    }
+
     /// Record the performance.
     fn report(&mut self) {
         self.reports.push(self.report.clone());
@@ -188,13 +906,14 @@ pub trait Algorithm<F: ObjFunc>: Sized {
         self.base().ub(i)
     }
 
-    /// Assign from source.
-    fn assign_from<'a, A>(&mut self, i: usize, f: f64, v: A)
+    /// Assign from source with its fitness `f` and constraint violation `violation`.
+    fn assign_from<'a, A>(&mut self, i: usize, f: f64, violation: f64, v: A)
     where
         A: AsArray<'a, f64>,
     {
         let b = self.base_mut();
         b.fitness[i] = f;
+        b.violation[i] = violation;
         b.pool.slice_mut(s![i, ..]).assign(&v.into());
     }
 
@@ -203,11 +922,11 @@ pub trait Algorithm<F: ObjFunc>: Sized {
         let b = self.base_mut();
         let mut best = 0;
         for i in 0..b.pop_num {
-            if b.fitness[i] < b.fitness[best] {
+            if b.is_better_at(i, best) {
                 best = i;
             }
         }
-        if b.fitness[best] < b.report.best_f {
+        if b.is_best(b.fitness[best], b.violation[best]) {
             b.set_best(best);
         }
     }
@@ -215,96 +934,88 @@ pub trait Algorithm<F: ObjFunc>: Sized {
     /// Initialize population.
     fn init_pop(&mut self) {
         let b = self.base_mut();
-        #[cfg(feature = "parallel")]
-        let mut tasks = crate::thread_pool::ThreadPool::new();
-        let mut best = 0;
         for i in 0..b.pop_num {
             for s in 0..b.dim {
-                b.pool[[i, s]] = rand!(b.lb(s), b.ub(s));
-            }
-            #[cfg(feature = "parallel")]
-            tasks.insert(i, b.func.clone(), b.report.clone(), b.pool.slice(s![i, ..]));
-            #[cfg(not(feature = "parallel"))]
-            {
-                b.fitness(i);
-                if b.fitness[i] < b.fitness[best] {
-                    best = i;
-                }
+                b.pool[[i, s]] = b.random(s);
             }
         }
-        #[cfg(feature = "parallel")]
-        for (i, f) in tasks {
-            b.fitness[i] = f;
-            if b.fitness[i] < b.fitness[best] {
+        let (fitness, violation) = b.evaluate(b.pool.view());
+        b.fitness = fitness;
+        b.violation = violation;
+        let mut best = 0;
+        for i in 0..b.pop_num {
+            if b.is_better_at(i, best) {
                 best = i;
             }
         }
         b.set_best(best);
     }
 
-    /// Check the bounds of the index `s` with the value `v`.
-    fn check(&self, s: usize, v: f64) -> f64 {
-        if v > self.ub(s) {
-            self.ub(s)
-        } else if v < self.lb(s) {
-            self.lb(s)
-        } else {
-            v
-        }
+    /// Check the bounds of the index `s` with the value `v`,
+    /// `parent` is the value before moving.
+    ///
+    /// See [`AlgorithmBase::check`].
+    #[inline(always)]
+    fn check(&mut self, s: usize, v: f64, parent: f64) -> f64 {
+        self.base_mut().check(s, v, parent)
     }
 
     #[doc(hidden)]
-    fn run<C>(mut self, mut callback: impl Callback<C>) -> Self {
+    fn run<C>(mut self, mut callback: impl Callback<C>) -> Result<Self, Error> {
+        if let Some(e) = self.base_mut().error.take() {
+            return Err(e);
+        }
         let time_start = Instant::now();
         self.init_pop();
-        self.base_mut().report.update_time(time_start);
+        let b = self.base_mut();
+        if let Some(e) = b.error.take() {
+            return Err(e);
+        }
+        b.report.nan = b.nan.load(Relaxed);
+        b.report.eval = b.eval.load(Relaxed);
+        b.update_feasible();
+        b.report.update_time(time_start);
         self.init();
+        self.base_mut().update_stats();
         if callback.call(self.base().report.clone()) {
-            return self;
+            return Ok(self);
+        }
+        let b = self.base_mut();
+        b.report();
+        let mut state = TaskState::new(time_start, b.report.best_f);
+        // The task may be done by the initial population
+        b.stopped_by = state.check(b);
+        if b.stopped_by.is_some() {
+            return Ok(self);
         }
-        self.base_mut().report();
-        let mut last_diff = 0.;
         loop {
-            let best_f = {
-                let r = &mut self.base_mut().report;
-                r.next_gen();
-                r.update_time(time_start);
-                r.best_f
-            };
+            let r = &mut self.base_mut().report;
+            r.next_gen();
+            r.update_time(time_start);
             self.generation();
             let b = self.base_mut();
+            if let Some(e) = b.error.take() {
+                return Err(e);
+            }
+            b.report.nan = b.nan.load(Relaxed);
+            b.report.eval = b.eval.load(Relaxed);
+            b.update_feasible();
             if b.report.gen % b.rpt == 0 {
+                b.update_stats();
                 if callback.call(b.report.clone()) {
                     break;
                 }
                 b.report();
+            } else {
+                b.report.stats = None;
             }
-            match b.task {
-                Task::MaxGen(v) => {
-                    if b.report.gen >= v {
-                        break;
-                    }
-                }
-                Task::MinFit(v) => {
-                    if b.report.best_f <= v {
-                        break;
-                    }
-                }
-                Task::MaxTime(v) => {
-                    if (Instant::now() - time_start).as_secs_f32() >= v {
-                        break;
-                    }
-                }
-                Task::SlowDown(v) => {
-                    let diff = best_f - b.report.best_f;
-                    if last_diff > 0. && diff / last_diff >= v {
-                        break;
-                    }
-                    last_diff = diff;
-                }
+            b.stopped_by = state.check(b);
+            if b.stopped_by.is_some() {
+                break;
             }
         }
-        self
+        self.base_mut().report.update_time(time_start);
+        Ok(self)
     }
 }
 
@@ -313,10 +1024,47 @@ pub trait Algorithm<F: ObjFunc>: Sized {
 /// Users can simply obtain their solution and see the result.
 pub trait Solver<F: ObjFunc>: Algorithm<F> {
     /// Create the task and calling [`Algorithm::run`].
+    ///
+    /// # Panics
+    ///
+    /// Panic if the run is aborted by [`FailurePolicy::Abort`],
+    /// or the task has an empty condition.
+    /// Use [`Solver::try_solve`] to obtain the error instead.
     fn solve<C>(func: F, settings: Self::Setting, callback: impl Callback<C>) -> Self {
+        Self::try_solve(func, settings, callback).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create the task and calling [`Algorithm::run`],
+    /// return the error if the run is aborted by [`FailurePolicy::Abort`],
+    /// or [`Error::EmptyTask`] if the task has an empty condition.
+    fn try_solve<C>(
+        func: F,
+        settings: Self::Setting,
+        callback: impl Callback<C>,
+    ) -> Result<Self, Error> {
         Self::create(func, settings).run(callback)
     }
 
+    /// Create the task and calling [`Algorithm::run`], then turn into the [`Solution`].
+    ///
+    /// # Panics
+    ///
+    /// Panic as [`Solver::solve`].
+    /// Use [`Solver::try_solution`] to obtain the error instead.
+    fn solution<C>(func: F, settings: Self::Setting, callback: impl Callback<C>) -> Solution<F> {
+        Self::solve(func, settings, callback).into_solution()
+    }
+
+    /// Create the task and calling [`Algorithm::run`], then turn into the [`Solution`],
+    /// return the error as [`Solver::try_solve`].
+    fn try_solution<C>(
+        func: F,
+        settings: Self::Setting,
+        callback: impl Callback<C>,
+    ) -> Result<Solution<F>, Error> {
+        Self::try_solve(func, settings, callback).map(Self::into_solution)
+    }
+
     /// Get the history for plotting.
     fn history(&self) -> Vec<Report> {
         self.base().reports.clone()
@@ -329,11 +1077,43 @@ pub trait Solver<F: ObjFunc>: Algorithm<F> {
         (b.best.clone(), b.report.best_f)
     }
 
+    /// Return the typed values of the best parameters.
+    /// The algorithm must be executed once.
+    ///
+    /// See [`ObjFunc::variable`].
+    fn values(&self) -> Vec<Value> {
+        let b = self.base();
+        (0..b.dim)
+            .map(|s| b.func.variable(s).value(b.best[s]))
+            .collect()
+    }
+
     /// Get the result of the objective function.
     fn result(&self) -> F::Result {
         let b = self.base();
         b.func.result(&b.best)
     }
+
+    /// Turn into the [`Solution`], the history is moved without copying.
+    /// The algorithm must be executed once.
+    fn into_solution(mut self) -> Solution<F> {
+        let result = self.result();
+        let b = self.base_mut();
+        Solution {
+            best: b.best.clone(),
+            best_f: b.report.best_f,
+            violation: b.report.violation,
+            result,
+            history: std::mem::take(&mut b.reports),
+            eval: b.report.eval,
+            time: b.report.time,
+            seed: b.rng.seed(),
+            termination: match b.stopped_by.take() {
+                Some(task) => Termination::Task(task),
+                None => Termination::Callback,
+            },
+        }
+    }
 }
 
 impl<F, T> Solver<F> for T
@@ -0,0 +1,85 @@
+/// The kind of a decision variable, see [`crate::ObjFunc::variable`].
+///
+/// The values are stored as floating point numbers in the population,
+/// and they are always snapped to the valid values by the algorithms.
+/// ```
+/// use metaheuristics_nature::{Value, Variable};
+///
+/// assert_eq!(Variable::Integer.snap(2.6, 0., 10.), 3.);
+/// assert_eq!(Variable::Integer.value(3.), Value::Int(3));
+/// let gear = Variable::Discrete(&[12., 16., 20., 24.]);
+/// assert_eq!(gear.snap(17.5, 12., 24.), 16.);
+/// assert_eq!(gear.value(16.), Value::Discrete(1));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Variable<'a> {
+    /// Real number between the bounds.
+    Continuous,
+    /// Integer between the bounds.
+    Integer,
+    /// One of the allowed values.
+    ///
+    /// The allowed values should be placed between the bounds, and should not be empty.
+    Discrete(&'a [f64]),
+}
+
+impl Variable<'_> {
+    /// Snap the value `v` to the nearest valid value.
+    ///
+    /// The value out of the bounds is only rounded for the integer variables,
+    /// or returned as is for the other variables.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the allowed values of [`Variable::Discrete`] are empty.
+    pub fn snap(&self, v: f64, lb: f64, ub: f64) -> f64 {
+        let inside = v >= lb && v <= ub;
+        match self {
+            Self::Continuous => v,
+            Self::Integer if inside => v.round().max(lb.ceil()).min(ub.floor()),
+            Self::Integer => v.round(),
+            Self::Discrete(values) if inside => values[self.nearest(v)],
+            Self::Discrete(_) => v,
+        }
+    }
+
+    /// Convert a valid value to the typed value.
+    ///
+    /// # Panics
+    ///
+    /// Panic if the allowed values of [`Variable::Discrete`] are empty.
+    pub fn value(&self, v: f64) -> Value {
+        match self {
+            Self::Continuous => Value::Float(v),
+            Self::Integer => Value::Int(v as i64),
+            Self::Discrete(_) => Value::Discrete(self.nearest(v)),
+        }
+    }
+
+    fn nearest(&self, v: f64) -> usize {
+        match self {
+            Self::Discrete(values) => {
+                assert!(!values.is_empty(), "empty values of the discrete variable!");
+                let mut best = 0;
+                for (i, x) in values.iter().enumerate() {
+                    if (x - v).abs() < (values[best] - v).abs() {
+                        best = i;
+                    }
+                }
+                best
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// The typed value of a decision variable, see [`crate::Solver::values`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// Value of the continuous variable.
+    Float(f64),
+    /// Value of the integer variable.
+    Int(i64),
+    /// Index of the allowed values of the discrete variable.
+    Discrete(usize),
+}